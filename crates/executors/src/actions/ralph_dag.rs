@@ -0,0 +1,286 @@
+//! Multi-spec DAG scheduling for Ralph loops.
+//!
+//! A single `RalphLoopRequest` handles exactly one spec file. `RalphDagRequest`
+//! lets a caller submit several specs with declared dependencies and have
+//! them scheduled like a build graph: independent specs run concurrently
+//! (gated by the shared jobserver), a spec only starts once every spec it
+//! depends on has exited successfully, and a failing spec prunes its
+//! dependents instead of running them.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::ralph_loop::{RalphLoopMode, RalphLoopRequest, StopPolicy};
+use crate::{
+    approvals::ExecutorApprovalService, env::ExecutionEnv, executors::ExecutorError,
+    jobserver::Jobserver,
+};
+
+/// One spec in the DAG: what to run, and what must finish first.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct SpecNode {
+    /// Unique within the request; also the spec's filename (without .md).
+    pub filename: String,
+    pub content: String,
+    pub mode: RalphLoopMode,
+    #[serde(default)]
+    pub max_iterations: u32,
+    /// Filenames of specs that must complete successfully before this one
+    /// may start.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+}
+
+/// A request carrying multiple specs scheduled as a dependency DAG.
+///
+/// Held for the integration PR rather than wired into a route in this
+/// series: a `Task` today maps to exactly one spec, and `RalphExecutor`
+/// dispatches a task as a single `loop.sh` invocation, so there's no
+/// existing caller with more than one spec to schedule. Standing up a
+/// multi-spec DAG means extending that data model first (what owns the
+/// `Vec<SpecNode>` - one task or several?), which is a product decision
+/// for that PR, not something to assume here.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct RalphDagRequest {
+    /// Path to the .ralph directory (e.g., /path/to/project/.ralph)
+    pub ralph_path: String,
+    pub specs: Vec<SpecNode>,
+}
+
+/// Per-node status, surfaced so callers can render scheduling progress.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeStatus {
+    Pending,
+    Running,
+    Done,
+    Failed,
+    /// Never ran because an upstream dependency failed.
+    Skipped,
+}
+
+/// Final status of every node in the DAG, keyed by `filename`.
+pub type DagRunReport = HashMap<String, NodeStatus>;
+
+impl RalphDagRequest {
+    /// Compute a topological run order, grouped into concurrency-safe
+    /// layers: every node in a layer has all its dependencies satisfied by
+    /// earlier layers, so a layer's nodes can run concurrently. Returns a
+    /// typed error naming the cycle if the graph isn't a DAG.
+    fn topological_layers(&self) -> Result<Vec<Vec<String>>, ExecutorError> {
+        let mut in_degree: HashMap<&str, usize> = HashMap::new();
+        let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+
+        for node in &self.specs {
+            in_degree.entry(&node.filename).or_insert(0);
+            for dep in &node.depends_on {
+                *in_degree.entry(&node.filename).or_insert(0) += 1;
+                dependents.entry(dep.as_str()).or_default().push(&node.filename);
+            }
+        }
+
+        let mut layers = Vec::new();
+        let mut remaining = in_degree.clone();
+        let mut scheduled: HashSet<&str> = HashSet::new();
+
+        while scheduled.len() < self.specs.len() {
+            let layer: Vec<&str> = remaining
+                .iter()
+                .filter(|(name, &deg)| deg == 0 && !scheduled.contains(**name))
+                .map(|(name, _)| *name)
+                .collect();
+
+            if layer.is_empty() {
+                let cycle: Vec<String> = remaining
+                    .iter()
+                    .filter(|(name, _)| !scheduled.contains(**name))
+                    .map(|(name, _)| name.to_string())
+                    .collect();
+                return Err(ExecutorError::Io(std::io::Error::other(format!(
+                    "RalphDagRequest has a dependency cycle among specs: {}",
+                    cycle.join(", ")
+                ))));
+            }
+
+            for &name in &layer {
+                scheduled.insert(name);
+                if let Some(next) = dependents.get(name) {
+                    for &dependent in next {
+                        *remaining.get_mut(dependent).unwrap() -= 1;
+                    }
+                }
+            }
+
+            layers.push(layer.into_iter().map(str::to_string).collect());
+        }
+
+        Ok(layers)
+    }
+
+    /// Write all spec files up front, then run the DAG: each layer runs
+    /// concurrently (gated by `jobserver`), and a failing node marks every
+    /// transitive dependent `Skipped` instead of running it.
+    pub async fn run(
+        &self,
+        current_dir: &Path,
+        approvals: Arc<dyn ExecutorApprovalService>,
+        env: Arc<ExecutionEnv>,
+        jobserver: Arc<Jobserver>,
+    ) -> Result<DagRunReport, ExecutorError> {
+        let layers = self.topological_layers()?;
+
+        let specs_dir = Path::new(&self.ralph_path).join("specs");
+        tokio::fs::create_dir_all(&specs_dir)
+            .await
+            .map_err(ExecutorError::Io)?;
+        for node in &self.specs {
+            let spec_path = specs_dir.join(format!("{}.md", node.filename));
+            tokio::fs::write(&spec_path, &node.content)
+                .await
+                .map_err(ExecutorError::Io)?;
+        }
+
+        let by_name: HashMap<&str, &SpecNode> =
+            self.specs.iter().map(|n| (n.filename.as_str(), n)).collect();
+
+        let mut report: DagRunReport = self
+            .specs
+            .iter()
+            .map(|n| (n.filename.clone(), NodeStatus::Pending))
+            .collect();
+
+        for layer in layers {
+            let mut handles = Vec::new();
+
+            for name in layer {
+                let node = by_name[name.as_str()];
+                let upstream_failed = node
+                    .depends_on
+                    .iter()
+                    .any(|dep| report.get(dep) != Some(&NodeStatus::Done));
+
+                if upstream_failed {
+                    report.insert(name, NodeStatus::Skipped);
+                    continue;
+                }
+
+                let request = RalphLoopRequest {
+                    ralph_path: self.ralph_path.clone(),
+                    task_spec: node.content.clone(),
+                    spec_filename: node.filename.clone(),
+                    mode: node.mode.clone(),
+                    max_iterations: node.max_iterations,
+                    dry_run: false,
+                    sandbox: Default::default(),
+                    stop_policy: StopPolicy::MaxIterations,
+                };
+                let approvals = approvals.clone();
+                let env = env.clone();
+                let jobserver = jobserver.clone();
+                let current_dir = current_dir.to_path_buf();
+
+                handles.push((
+                    name,
+                    tokio::spawn(async move {
+                        let mut spawned = request
+                            .spawn_bounded(&current_dir, approvals, &env, jobserver)
+                            .await?;
+                        spawned.child.wait().await.map_err(ExecutorError::Io)
+                    }),
+                ));
+            }
+
+            for (name, handle) in handles {
+                let status = match handle.await {
+                    Ok(Ok(status)) if status.success() => NodeStatus::Done,
+                    _ => NodeStatus::Failed,
+                };
+                report.insert(name, status);
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(filename: &str, depends_on: &[&str]) -> SpecNode {
+        SpecNode {
+            filename: filename.to_string(),
+            content: String::new(),
+            mode: RalphLoopMode::Build,
+            max_iterations: 0,
+            depends_on: depends_on.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn dag(specs: Vec<SpecNode>) -> RalphDagRequest {
+        RalphDagRequest {
+            ralph_path: "/tmp/test-ralph".to_string(),
+            specs,
+        }
+    }
+
+    #[test]
+    fn test_topological_layers_runs_independent_nodes_in_one_layer() {
+        let request = dag(vec![node("a", &[]), node("b", &[])]);
+        let layers = request.topological_layers().unwrap();
+
+        assert_eq!(layers.len(), 1);
+        let mut only_layer = layers[0].clone();
+        only_layer.sort();
+        assert_eq!(only_layer, vec!["a".to_string(), "b".to_string()]);
+    }
+
+    #[test]
+    fn test_topological_layers_orders_a_chain() {
+        let request = dag(vec![node("c", &["b"]), node("a", &[]), node("b", &["a"])]);
+        let layers = request.topological_layers().unwrap();
+
+        assert_eq!(
+            layers,
+            vec![
+                vec!["a".to_string()],
+                vec!["b".to_string()],
+                vec!["c".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_topological_layers_groups_a_diamond_by_depth() {
+        // a -> {b, c} -> d
+        let request = dag(vec![
+            node("a", &[]),
+            node("b", &["a"]),
+            node("c", &["a"]),
+            node("d", &["b", "c"]),
+        ]);
+        let layers = request.topological_layers().unwrap();
+
+        assert_eq!(layers.len(), 3);
+        assert_eq!(layers[0], vec!["a".to_string()]);
+        let mut middle = layers[1].clone();
+        middle.sort();
+        assert_eq!(middle, vec!["b".to_string(), "c".to_string()]);
+        assert_eq!(layers[2], vec!["d".to_string()]);
+    }
+
+    #[test]
+    fn test_topological_layers_rejects_a_cycle() {
+        let request = dag(vec![node("a", &["b"]), node("b", &["a"])]);
+        let err = request.topological_layers().unwrap_err();
+
+        let message = err.to_string();
+        assert!(message.contains('a') && message.contains('b'));
+    }
+}
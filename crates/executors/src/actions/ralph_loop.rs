@@ -1,4 +1,4 @@
-use std::{path::Path, sync::Arc};
+use std::{collections::HashMap, path::Path, sync::Arc};
 
 use async_trait::async_trait;
 use command_group::AsyncCommandGroup;
@@ -11,8 +11,46 @@ use crate::{
     approvals::ExecutorApprovalService,
     env::ExecutionEnv,
     executors::{ExecutorError, SpawnedChild},
+    jobserver::{JobToken, Jobserver},
+    sandbox::{SandboxPolicy, SandboxResult, SandboxSession},
 };
 
+/// A single resolved invocation, in the shape of Cargo's `--build-plan` output:
+/// enough to run (or diff) the command without actually running it.
+///
+/// Held for the integration PR rather than wired into a route in this
+/// series: `RalphLoopRequest::ralph_path` is the `.ralph` convention (see
+/// its own doc comment), while the executor that's actually dispatched
+/// today (`RalphExecutor`) writes the spec under `.ralph-vibe-kanban`
+/// instead - the two were never reconciled, so a preview built from this
+/// type wouldn't describe what `RalphExecutor` actually runs. Resolving
+/// that naming mismatch belongs with whichever PR makes this the live
+/// implementation, not bolted on ad hoc here.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct PlannedInvocation {
+    pub program: String,
+    pub args: Vec<String>,
+    pub env: HashMap<String, String>,
+    pub cwd: String,
+    /// Files this invocation would write before running, e.g. the spec file.
+    pub outputs: Vec<String>,
+}
+
+/// A fully-resolved, side-effect-free preview of what a loop would run.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct RalphExecutionPlan {
+    pub invocations: Vec<PlannedInvocation>,
+}
+
+/// A spawned child bundled with the jobserver token that gates it. Holding
+/// the token alongside the child (instead of releasing it eagerly) means the
+/// slot isn't freed until the caller is done with the process, matching the
+/// same lifetime `kill_on_drop` already ties the process to.
+pub struct BoundedSpawn {
+    pub child: SpawnedChild,
+    _token: JobToken,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub enum RalphLoopMode {
     /// Plan mode - generates implementation plan
@@ -34,6 +72,52 @@ pub struct RalphLoopRequest {
     /// Maximum iterations (0 for unlimited)
     #[serde(default)]
     pub max_iterations: u32,
+    /// When true, resolve the invocation but never touch the filesystem or
+    /// spawn a process. Use `build_plan` to retrieve the preview; `spawn`
+    /// refuses to run while this is set.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Opt-in overlayfs sandboxing for this iteration; see `spawn_sandboxed`.
+    #[serde(default)]
+    pub sandbox: SandboxPolicy,
+    /// Governs when `spawn_iterating` halts a multi-iteration run.
+    #[serde(default)]
+    pub stop_policy: StopPolicy,
+}
+
+/// Controls when a multi-iteration run stops, instead of leaving that
+/// decision entirely to `loop.sh`'s own exit semantics.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, Default)]
+pub enum StopPolicy {
+    /// Run until `max_iterations` is reached (0 = unbounded); today's
+    /// behavior, where the crate never inspects individual iterations.
+    #[default]
+    MaxIterations,
+    /// Abort as soon as an iteration exits non-zero.
+    FailFast,
+    /// Stop once an iteration writes the given marker file under
+    /// `ralph_path`, signalling convergence (e.g. `.ralph/CONVERGED`).
+    OnSentinel { marker_file: String },
+}
+
+/// Outcome of a single iteration under `spawn_iterating`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct IterationResult {
+    pub iteration: u32,
+    pub exit_code: Option<i32>,
+    pub duration_ms: u128,
+}
+
+/// Summary of a multi-iteration run, propagating the final meaningful exit
+/// code upward instead of discarding it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct RunSummary {
+    pub iterations: Vec<IterationResult>,
+    /// Exit code of the last iteration that ran.
+    pub final_exit_code: Option<i32>,
+    /// True if the run stopped because `OnSentinel`'s marker appeared,
+    /// rather than by exhausting `max_iterations` or failing.
+    pub converged: bool,
 }
 
 impl RalphLoopRequest {
@@ -44,6 +128,9 @@ impl RalphLoopRequest {
             spec_filename,
             mode: RalphLoopMode::Plan,
             max_iterations: 5, // Default to 5 iterations for planning
+            dry_run: false,
+            sandbox: SandboxPolicy::None,
+            stop_policy: StopPolicy::default(),
         }
     }
 
@@ -54,8 +141,285 @@ impl RalphLoopRequest {
             spec_filename,
             mode: RalphLoopMode::Build,
             max_iterations: 0, // No limit for build mode
+            dry_run: false,
+            sandbox: SandboxPolicy::None,
+            stop_policy: StopPolicy::default(),
+        }
+    }
+
+    /// Resolve the fully-specified invocation without writing the spec file
+    /// or spawning `loop.sh`, mirroring Cargo's `--build-plan` JSON output.
+    /// Lets callers preview (and diff) what a loop will do before committing.
+    pub fn build_plan(&self, current_dir: &Path, env: &ExecutionEnv) -> RalphExecutionPlan {
+        let ralph_dir = Path::new(&self.ralph_path);
+        let specs_dir = ralph_dir.join("specs");
+        let spec_path = specs_dir.join(format!("{}.md", self.spec_filename));
+        let loop_script = ralph_dir.join("loop.sh");
+
+        // Build the same command spawn() would, then introspect it, so the
+        // plan can never drift from what actually runs.
+        let mut command = Command::new(&loop_script);
+        command.current_dir(current_dir);
+        if matches!(self.mode, RalphLoopMode::Plan) {
+            command.arg("plan");
+        }
+        if self.max_iterations > 0 {
+            command.arg(self.max_iterations.to_string());
+        }
+        env.apply_to_command(&mut command);
+
+        let std_command = command.as_std();
+        let args = std_command
+            .get_args()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect();
+        let resolved_env = std_command
+            .get_envs()
+            .filter_map(|(k, v)| v.map(|v| (k.to_string_lossy().to_string(), v.to_string_lossy().to_string())))
+            .collect();
+
+        RalphExecutionPlan {
+            invocations: vec![PlannedInvocation {
+                program: loop_script.to_string_lossy().to_string(),
+                args,
+                env: resolved_env,
+                cwd: current_dir.to_string_lossy().to_string(),
+                outputs: vec![spec_path.to_string_lossy().to_string()],
+            }],
+        }
+    }
+
+    /// Spawn the loop gated on the given jobserver: acquire a token before
+    /// `group_spawn`, export it to the child (and anything it shells out to)
+    /// via the standard `MAKEFLAGS`/`--jobserver-auth=<r>,<w>` convention. The
+    /// token travels in the returned `BoundedSpawn` and is released when that
+    /// is dropped, the same point at which `kill_on_drop` ends the process.
+    pub async fn spawn_bounded(
+        &self,
+        current_dir: &Path,
+        _approvals: Arc<dyn ExecutorApprovalService>,
+        env: &ExecutionEnv,
+        jobserver: Arc<Jobserver>,
+    ) -> Result<BoundedSpawn, ExecutorError> {
+        if self.dry_run {
+            return Err(ExecutorError::Io(std::io::Error::other(
+                "RalphLoopRequest is in dry_run mode; call build_plan() for a preview instead of spawn_bounded()",
+            )));
+        }
+
+        // The implicit token is never placed in the pipe, so every spawn
+        // (including the first) must acquire before it's allowed to run.
+        let token = jobserver.acquire().await?;
+
+        let ralph_dir = Path::new(&self.ralph_path);
+        let specs_dir = ralph_dir.join("specs");
+        tokio::fs::create_dir_all(&specs_dir)
+            .await
+            .map_err(ExecutorError::Io)?;
+
+        let spec_path = specs_dir.join(format!("{}.md", self.spec_filename));
+        tokio::fs::write(&spec_path, &self.task_spec)
+            .await
+            .map_err(ExecutorError::Io)?;
+
+        let loop_script = ralph_dir.join("loop.sh");
+        if !loop_script.exists() {
+            return Err(ExecutorError::ExecutableNotFound {
+                program: loop_script.to_string_lossy().to_string(),
+            });
+        }
+
+        let mut command = Command::new(&loop_script);
+        command
+            .kill_on_drop(true)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .current_dir(current_dir);
+
+        match self.mode {
+            RalphLoopMode::Plan => {
+                command.arg("plan");
+            }
+            RalphLoopMode::Build => {}
+        }
+        if self.max_iterations > 0 {
+            command.arg(self.max_iterations.to_string());
+        }
+
+        env.apply_to_command(&mut command);
+        // MAKEFLAGS propagates the jobserver fds to any sub-make/cargo the
+        // agent loop invokes, so total concurrency stays bounded by the pool.
+        command.env("MAKEFLAGS", jobserver.make_flags());
+
+        let child = command.group_spawn()?;
+
+        Ok(BoundedSpawn {
+            child: child.into(),
+            _token: token,
+        })
+    }
+
+    /// Run the loop to completion against an isolated overlay view of
+    /// `current_dir` per `self.sandbox`, then commit or discard the changes.
+    /// Unlike `spawn`/`spawn_bounded`, this awaits the whole run: a sandboxed
+    /// iteration can't hand the caller a live child, since the commit/
+    /// discard decision can only be made once the process group has exited.
+    ///
+    /// Builds its own command instead of going through `Executable::spawn`
+    /// because the overlay mount has to be entered via a `pre_exec` hook
+    /// attached directly to the `Command` that execs `loop.sh` — see
+    /// `SandboxSession::apply_to_command`.
+    ///
+    /// Held for the integration PR: `RalphExecutor` has no `SandboxPolicy`
+    /// field and nothing in `routes::ralph` accepts one, so there's no
+    /// request path that could reach this today. Exposing it means
+    /// deciding whether sandboxing becomes a `RalphExecutor` option or
+    /// stays behind this separate request type - a call for that PR, not
+    /// one to make implicitly by wiring a route here.
+    pub async fn spawn_sandboxed(
+        &self,
+        current_dir: &Path,
+        _approvals: Arc<dyn ExecutorApprovalService>,
+        env: &ExecutionEnv,
+    ) -> Result<SandboxResult, ExecutorError> {
+        if self.dry_run {
+            return Err(ExecutorError::Io(std::io::Error::other(
+                "RalphLoopRequest is in dry_run mode; call build_plan() for a preview instead of spawn_sandboxed()",
+            )));
+        }
+
+        let commit_on_success = match self.sandbox {
+            SandboxPolicy::None => {
+                return Err(ExecutorError::Io(std::io::Error::other(
+                    "spawn_sandboxed called with SandboxPolicy::None; use spawn instead",
+                )));
+            }
+            SandboxPolicy::Overlay { commit_on_success } => commit_on_success,
+        };
+
+        let session = SandboxSession::setup(current_dir, commit_on_success).await?;
+
+        let ralph_dir = Path::new(&self.ralph_path);
+        let specs_dir = ralph_dir.join("specs");
+        tokio::fs::create_dir_all(&specs_dir)
+            .await
+            .map_err(ExecutorError::Io)?;
+
+        let spec_path = specs_dir.join(format!("{}.md", self.spec_filename));
+        tokio::fs::write(&spec_path, &self.task_spec)
+            .await
+            .map_err(ExecutorError::Io)?;
+
+        let loop_script = ralph_dir.join("loop.sh");
+        if !loop_script.exists() {
+            return Err(ExecutorError::ExecutableNotFound {
+                program: loop_script.to_string_lossy().to_string(),
+            });
+        }
+
+        let mut command = Command::new(&loop_script);
+        command
+            .kill_on_drop(true)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped());
+
+        match self.mode {
+            RalphLoopMode::Plan => {
+                command.arg("plan");
+            }
+            RalphLoopMode::Build => {}
+        }
+        if self.max_iterations > 0 {
+            command.arg(self.max_iterations.to_string());
         }
+
+        env.apply_to_command(&mut command);
+        session.apply_to_command(&mut command);
+
+        let child = match command.group_spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                // Tear down the scratch dirs even if the child never started.
+                let _ = session.teardown(false).await;
+                return Err(ExecutorError::Io(e));
+            }
+        };
+        let mut child: SpawnedChild = child.into();
+
+        let status = child.wait().await.map_err(ExecutorError::Io)?;
+        session.teardown(status.success()).await
     }
+
+    /// Drive the loop one iteration at a time instead of handing `loop.sh`
+    /// an unbounded iteration count, so `self.stop_policy` decides when to
+    /// halt rather than the shell script's own (opaque) exit semantics.
+    /// Records each iteration's exit code and duration and propagates the
+    /// final meaningful exit code upward.
+    ///
+    /// Held for the integration PR: giving `RalphExecutor` (the executor
+    /// that's actually dispatched) a per-iteration stop policy means either
+    /// porting this loop onto its `NormalizedEntry`/`MsgStore` reporting -
+    /// `RalphExecutor::spawn_watched` and `spawn_supervised` already own
+    /// multi-invocation loops of their own, and they shouldn't compete
+    /// over the same `loop.sh` process - or making `RalphLoopRequest` the
+    /// executor outright. Both are real design decisions for that PR, not
+    /// something to default into from here.
+    pub async fn spawn_iterating(
+        &self,
+        current_dir: &Path,
+        approvals: Arc<dyn ExecutorApprovalService>,
+        env: &ExecutionEnv,
+    ) -> Result<RunSummary, ExecutorError> {
+        let mut iterations = Vec::new();
+        let mut converged = false;
+        let mut iteration: u32 = 0;
+
+        loop {
+            iteration += 1;
+
+            let mut single_iteration = self.clone();
+            single_iteration.max_iterations = 1;
+
+            let started = std::time::Instant::now();
+            let mut child = Executable::spawn(&single_iteration, current_dir, approvals.clone(), env).await?;
+            let status = child.wait().await.map_err(ExecutorError::Io)?;
+            let duration_ms = started.elapsed().as_millis();
+
+            iterations.push(IterationResult {
+                iteration,
+                exit_code: status.code(),
+                duration_ms,
+            });
+
+            let should_stop = match &self.stop_policy {
+                StopPolicy::FailFast => !status.success(),
+                StopPolicy::OnSentinel { marker_file } => {
+                    let marker = Path::new(&self.ralph_path).join(marker_file);
+                    if tokio::fs::try_exists(&marker).await.unwrap_or(false) {
+                        converged = true;
+                        true
+                    } else {
+                        false
+                    }
+                }
+                StopPolicy::MaxIterations => false,
+            };
+
+            let exhausted = self.max_iterations > 0 && iteration >= self.max_iterations;
+            if should_stop || exhausted {
+                break;
+            }
+        }
+
+        Ok(RunSummary {
+            final_exit_code: iterations.last().and_then(|i| i.exit_code),
+            iterations,
+            converged,
+        })
+    }
+
 }
 
 #[async_trait]
@@ -66,6 +430,12 @@ impl Executable for RalphLoopRequest {
         _approvals: Arc<dyn ExecutorApprovalService>,
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
+        if self.dry_run {
+            return Err(ExecutorError::Io(std::io::Error::other(
+                "RalphLoopRequest is in dry_run mode; call build_plan() for a preview instead of spawn()",
+            )));
+        }
+
         let ralph_dir = Path::new(&self.ralph_path);
 
         // Ensure the specs directory exists
@@ -121,3 +491,59 @@ impl Executable for RalphLoopRequest {
         Ok(child.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> RalphLoopRequest {
+        RalphLoopRequest::new_build(
+            "/tmp/test-ralph".to_string(),
+            "do the thing".to_string(),
+            "task".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_new_plan_defaults_to_plan_mode_with_a_bounded_iteration_count() {
+        let request = RalphLoopRequest::new_plan(
+            "/tmp/test-ralph".to_string(),
+            "spec".to_string(),
+            "task".to_string(),
+        );
+
+        assert_eq!(request.mode, RalphLoopMode::Plan);
+        assert_eq!(request.max_iterations, 5);
+        assert!(!request.dry_run);
+        assert_eq!(request.sandbox, SandboxPolicy::None);
+        assert_eq!(request.stop_policy, StopPolicy::MaxIterations);
+    }
+
+    #[test]
+    fn test_new_build_defaults_to_build_mode_with_no_iteration_limit() {
+        let request = request();
+
+        assert_eq!(request.mode, RalphLoopMode::Build);
+        assert_eq!(request.max_iterations, 0);
+        assert!(!request.dry_run);
+    }
+
+    #[test]
+    fn test_single_iteration_clone_keeps_stop_policy_but_caps_max_iterations() {
+        // spawn_iterating clones the request per-iteration and pins
+        // max_iterations to 1 so loop.sh always runs exactly one step;
+        // everything else about the request must carry over unchanged.
+        let request = RalphLoopRequest {
+            max_iterations: 10,
+            stop_policy: StopPolicy::FailFast,
+            ..request()
+        };
+
+        let mut single_iteration = request.clone();
+        single_iteration.max_iterations = 1;
+
+        assert_eq!(single_iteration.max_iterations, 1);
+        assert_eq!(single_iteration.stop_policy, StopPolicy::FailFast);
+        assert_eq!(single_iteration.mode, request.mode);
+    }
+}
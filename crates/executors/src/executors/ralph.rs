@@ -6,11 +6,23 @@
 //!
 //! The spec is written to `.ralph-vibe-kanban/spec` and the loop script
 //! `.ralph-vibe-kanban/loop.sh` is invoked to start execution.
-
-use std::{path::Path, process::Stdio, sync::Arc};
+//!
+//! `spawn_watched` offers a third mode alongside the trait's plain `spawn`:
+//! instead of running `loop.sh` once, it watches the worktree and respawns
+//! the loop whenever a file changes, for spec-driven iterative builds.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+    time::Duration,
+};
 
 use async_trait::async_trait;
 use command_group::AsyncCommandGroup;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use notify::{RecursiveMode, Watcher};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
@@ -20,14 +32,86 @@ use workspace_utils::msg_store::MsgStore;
 use crate::{
     env::ExecutionEnv,
     executors::{ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
+    jobserver::Jobserver,
     logs::{
         NormalizedEntry, NormalizedEntryType,
         plain_text_processor::PlainTextLogProcessor,
         stderr_processor::normalize_stderr_logs,
         utils::EntryIndexProvider,
     },
+    transport::{ExecutionTransport, RemoteConnect},
 };
 
+/// Default debounce window for `watch` mode when `debounce_ms` is left at 0.
+const DEFAULT_DEBOUNCE_MS: u64 = 250;
+
+/// Directories always excluded from `watch` mode, on top of whatever the
+/// worktree's own `.gitignore` already excludes - without this, Ralph
+/// writing its own bookkeeping (or git updating its internals) would
+/// self-trigger an endless respawn loop.
+const ALWAYS_IGNORED: [&str; 2] = [".ralph-vibe-kanban", ".git"];
+
+/// Filename of the structured plan written under `.ralph-vibe-kanban` when
+/// `plan_format` is `Json`.
+const JSON_PLAN_FILENAME: &str = "plan.json";
+
+/// How often `normalize_logs` checks for `plan.json` to appear while a
+/// `Json`-format plan run is in progress.
+const JSON_PLAN_POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// How long to wait for `plan.json` before giving up. Generous, since a
+/// plan run can take a while to finish thinking before it writes output.
+const JSON_PLAN_POLL_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Total slot count of the jobserver shared by `spawn_watched`/
+/// `spawn_supervised` across every `RalphExecutor` instance. The implicit
+/// slot is never placed in the pipe and every invocation acquires a token,
+/// so at most `DEFAULT_JOBSERVER_PARALLELISM - 1` (3) `loop.sh` invocations
+/// run at once. Deliberately conservative - this bounds agent loops, each of
+/// which may themselves shell out to heavyweight toolchains.
+const DEFAULT_JOBSERVER_PARALLELISM: u32 = 4;
+
+/// The shared token pool gating `spawn_watched`/`spawn_supervised`. Not
+/// wired into the plain `spawn`/`spawn_follow_up` trait methods: those
+/// return a bare `SpawnedChild` - the type every `StandardCodingAgentExecutor`
+/// impl shares - to a caller that keeps running it well past this function
+/// returning, so there's no lifetime within `spawn` itself a token could be
+/// tied to without changing that shared type for every other executor.
+fn ralph_jobserver() -> &'static Arc<Jobserver> {
+    static JOBSERVER: std::sync::OnceLock<Arc<Jobserver>> = std::sync::OnceLock::new();
+    JOBSERVER.get_or_init(|| {
+        Jobserver::new(DEFAULT_JOBSERVER_PARALLELISM)
+            .expect("failed to create the Ralph jobserver pipe")
+    })
+}
+
+/// Output format for the plan `plan_mode` produces.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, TS, JsonSchema)]
+pub enum PlanFormat {
+    /// Free-text `IMPLEMENTATION_PLAN.md`, shown to the user as-is.
+    #[default]
+    Markdown,
+    /// Machine-readable `.ralph-vibe-kanban/plan.json`; `normalize_logs`
+    /// parses it into one log entry per step instead of prose.
+    Json,
+}
+
+/// One step of a `Json`-format plan, as written by `loop.sh` to
+/// `.ralph-vibe-kanban/plan.json`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct PlanStep {
+    /// Unique within the plan; referenced by other steps' `depends_on`.
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    /// Ids of steps that must be done before this one can start.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Paths (relative to the worktree) this step is expected to touch.
+    #[serde(default)]
+    pub files: Vec<String>,
+}
+
 /// Ralph executor configuration
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, TS, JsonSchema)]
 pub struct RalphExecutor {
@@ -35,17 +119,60 @@ pub struct RalphExecutor {
     /// When false, Ralph implements the existing plan.
     #[serde(default)]
     pub plan_mode: bool,
+    /// Marks this executor for file-watch re-execution (set by `with_watch`).
+    /// Nothing in this crate reads it: `spawn_watched` watches and respawns
+    /// whether or not it is set, and the plain `spawn`/`spawn_follow_up`
+    /// trait methods always run `loop.sh` exactly once.
+    #[serde(default)]
+    pub watch: bool,
+    /// Debounce window (milliseconds) `spawn_watched` uses to coalesce a
+    /// burst of file events into a single restart. `0` means "use
+    /// `DEFAULT_DEBOUNCE_MS`".
+    #[serde(default)]
+    pub debounce_ms: u64,
+    /// Requested format for the plan (`plan_mode` only). `Json` asks
+    /// `loop.sh` to additionally write a structured `plan.json`, which
+    /// `normalize_logs` parses into a dependency-ordered set of log
+    /// entries instead of leaving the plan as opaque prose.
+    #[serde(default)]
+    pub plan_format: PlanFormat,
+    /// Where `loop.sh` actually runs. `Remote` drives it over an SSH tunnel
+    /// against a worktree already synced to the far side; output is still
+    /// relayed back through the same local pipes, but under `Remote` the
+    /// forced remote pty merges the loop's stderr into the local stdout.
+    #[serde(default)]
+    pub transport: ExecutionTransport,
 }
 
 impl RalphExecutor {
     /// Create a new Ralph executor in plan mode
     pub fn plan() -> Self {
-        Self { plan_mode: true }
+        Self { plan_mode: true, ..Self::default() }
     }
 
     /// Create a new Ralph executor in build mode
     pub fn build() -> Self {
-        Self { plan_mode: false }
+        Self { plan_mode: false, ..Self::default() }
+    }
+
+    /// Enable file-watch re-execution (see `spawn_watched`).
+    pub fn with_watch(mut self, debounce_ms: u64) -> Self {
+        self.watch = true;
+        self.debounce_ms = debounce_ms;
+        self
+    }
+
+    /// Run `loop.sh` on a remote host over SSH instead of locally (see
+    /// `transport`).
+    pub fn with_remote(mut self, connect: RemoteConnect) -> Self {
+        self.transport = ExecutionTransport::Remote(connect);
+        self
+    }
+
+    /// Effective debounce window: `debounce_ms` if set, else the default.
+    fn debounce(&self) -> Duration {
+        let ms = if self.debounce_ms == 0 { DEFAULT_DEBOUNCE_MS } else { self.debounce_ms };
+        Duration::from_millis(ms)
     }
 
     /// Path to the Ralph directory in the worktree
@@ -58,46 +185,45 @@ impl RalphExecutor {
         self.ralph_dir(worktree_path).join("loop.sh")
     }
 
-    /// Path to the spec file
-    fn spec_path(&self, worktree_path: &Path) -> std::path::PathBuf {
-        self.ralph_dir(worktree_path).join("spec")
+    /// Path to the structured JSON plan written when `plan_format` is `Json`.
+    fn json_plan_path(&self, worktree_path: &Path) -> std::path::PathBuf {
+        self.ralph_dir(worktree_path).join(JSON_PLAN_FILENAME)
     }
 
-    /// Write the spec content to the spec file
+    /// Write the spec content to the spec file (local filesystem or remote
+    /// host, per `self.transport`).
     async fn write_spec(&self, worktree_path: &Path, spec: &str) -> Result<(), ExecutorError> {
-        let spec_path = self.spec_path(worktree_path);
-        info!("Ralph: writing spec to {:?}", spec_path);
+        info!("Ralph: writing spec via {:?}", self.transport);
 
-        tokio::fs::write(&spec_path, spec)
+        self.transport
+            .write_file(worktree_path, ".ralph-vibe-kanban/spec", spec)
             .await
             .map_err(|e| ExecutorError::Io(std::io::Error::other(format!(
                 "Failed to write spec file: {}",
                 e
-            ))))?;
-
-        Ok(())
+            ))))
     }
 
-    /// Validate that Ralph is set up in the worktree
-    fn validate_setup(&self, worktree_path: &Path) -> Result<(), ExecutorError> {
-        let ralph_dir = self.ralph_dir(worktree_path);
-        let loop_script = self.loop_script_path(worktree_path);
-
-        if !ralph_dir.exists() {
+    /// Validate that Ralph is set up in the worktree (local filesystem or
+    /// remote host, per `self.transport`). The executable-bit check only
+    /// runs for `Local`, since it's not worth a round trip over SSH for.
+    async fn validate_setup(&self, worktree_path: &Path) -> Result<(), ExecutorError> {
+        if !self.transport.path_exists(worktree_path, ".ralph-vibe-kanban").await {
             return Err(ExecutorError::Io(std::io::Error::other(
                 "Ralph not set up in worktree. Missing .ralph-vibe-kanban directory",
             )));
         }
 
-        if !loop_script.exists() {
+        if !self.transport.path_exists(worktree_path, ".ralph-vibe-kanban/loop.sh").await {
             return Err(ExecutorError::Io(std::io::Error::other(
                 "Ralph not set up in worktree. Missing .ralph-vibe-kanban/loop.sh",
             )));
         }
 
         #[cfg(unix)]
-        {
+        if matches!(self.transport, ExecutionTransport::Local) {
             use std::os::unix::fs::PermissionsExt;
+            let loop_script = self.loop_script_path(worktree_path);
             if let Ok(metadata) = std::fs::metadata(&loop_script) {
                 let permissions = metadata.permissions();
                 if permissions.mode() & 0o111 == 0 {
@@ -110,6 +236,259 @@ impl RalphExecutor {
 
         Ok(())
     }
+
+    /// Build the `loop.sh` invocation exactly as `spawn` runs it, without
+    /// actually spawning it - shared with `spawn_watched` so a respawn can
+    /// never drift from the first invocation.
+    fn build_loop_command(&self, current_dir: &Path) -> tokio::process::Command {
+        let mut args = Vec::new();
+        if self.plan_mode {
+            args.push("plan".to_string());
+            if self.plan_format == PlanFormat::Json {
+                args.push("--json-plan".to_string());
+            }
+        }
+
+        let mut cmd = self.transport.build_command(current_dir, ".ralph-vibe-kanban/loop.sh", &args);
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).kill_on_drop(true);
+
+        cmd
+    }
+
+    /// Spawn one `loop.sh` invocation (the common tail of `spawn` and each
+    /// respawn inside `spawn_watched`).
+    fn spawn_loop_child(&self, current_dir: &Path) -> Result<SpawnedChild, ExecutorError> {
+        let mut cmd = self.build_loop_command(current_dir);
+        debug!("Ralph: executing {:?}", cmd);
+        let child = cmd.group_spawn().map_err(ExecutorError::Io)?;
+        Ok(SpawnedChild::from(child))
+    }
+
+    /// Build a matcher combining the worktree's own `.gitignore` with
+    /// `ALWAYS_IGNORED`, used by `spawn_watched` to decide which filesystem
+    /// events are worth restarting over.
+    fn build_watch_ignore(&self, worktree_path: &Path) -> Gitignore {
+        let mut builder = GitignoreBuilder::new(worktree_path);
+        let _ = builder.add(worktree_path.join(".gitignore"));
+        for always_ignored in ALWAYS_IGNORED {
+            // A bad glob here shouldn't take down watch mode - at worst that
+            // one entry is skipped and the rest of the matcher still applies.
+            let _ = builder.add_line(None, always_ignored);
+        }
+        builder.build().unwrap_or_else(|_| Gitignore::empty())
+    }
+
+    /// Run `loop.sh` once, then keep re-running it whenever a file in
+    /// `current_dir` changes, until `stop_rx` reports `true`. Only one
+    /// invocation ever runs at a time: a debounced watcher coalesces a
+    /// burst of writes (e.g. a multi-file save) into a single restart
+    /// instead of respawning per-event, and the previous `SpawnedChild` is
+    /// always dropped (killing its process group via `kill_on_drop`)
+    /// before the next one is spawned.
+    pub async fn spawn_watched(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        mut stop_rx: tokio::sync::watch::Receiver<bool>,
+    ) -> Result<(), ExecutorError> {
+        info!("Ralph Executor: starting watch mode in {:?}", current_dir);
+        if !matches!(self.transport, ExecutionTransport::Local) {
+            return Err(ExecutorError::Io(std::io::Error::other(
+                "spawn_watched only supports ExecutionTransport::Local; it watches the local filesystem",
+            )));
+        }
+
+        self.validate_setup(current_dir).await?;
+        if prompt.trim().is_empty() {
+            return Err(ExecutorError::Io(std::io::Error::other(
+                "Task must have a description (spec) to use Ralph",
+            )));
+        }
+        self.write_spec(current_dir, prompt).await?;
+
+        let ignore = self.build_watch_ignore(current_dir);
+
+        let (tx, mut events) = tokio::sync::mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            if let Ok(event) = res {
+                let _ = tx.send(event);
+            }
+        })
+        .map_err(|e| ExecutorError::Io(std::io::Error::other(format!("Failed to start file watcher: {e}"))))?;
+        watcher
+            .watch(current_dir, RecursiveMode::Recursive)
+            .map_err(|e| {
+                ExecutorError::Io(std::io::Error::other(format!(
+                    "Failed to watch {:?}: {e}",
+                    current_dir
+                )))
+            })?;
+
+        let mut token = ralph_jobserver().acquire().await?;
+        let mut child = self.spawn_loop_child(current_dir)?;
+        info!("Ralph watch: initial loop.sh spawned");
+
+        let debounce = self.debounce();
+        let mut pending: HashSet<PathBuf> = HashSet::new();
+        let mut deadline: Option<tokio::time::Instant> = None;
+
+        loop {
+            let sleep = tokio::time::sleep(
+                deadline
+                    .map(|d| d.saturating_duration_since(tokio::time::Instant::now()))
+                    .unwrap_or(Duration::from_secs(3600)),
+            );
+            tokio::pin!(sleep);
+
+            tokio::select! {
+                changed = stop_rx.changed() => {
+                    if changed.is_err() || *stop_rx.borrow() {
+                        info!("Ralph watch: stopping, tearing down loop.sh");
+                        drop(child);
+                        drop(token);
+                        return Ok(());
+                    }
+                }
+                maybe_event = events.recv() => {
+                    let Some(event) = maybe_event else {
+                        warn!("Ralph watch: file watcher channel closed unexpectedly");
+                        drop(child);
+                        drop(token);
+                        return Ok(());
+                    };
+                    let relevant = event
+                        .paths
+                        .iter()
+                        .any(|path| !ignore.matched_path_or_any_parents(path, path.is_dir()).is_ignore());
+                    if relevant {
+                        pending.extend(event.paths);
+                        deadline = Some(tokio::time::Instant::now() + debounce);
+                    }
+                }
+                _ = &mut sleep, if deadline.is_some() => {
+                    if !pending.is_empty() {
+                        info!(
+                            "Ralph watch: {} path(s) changed, respawning loop.sh",
+                            pending.len()
+                        );
+                        pending.clear();
+                        drop(child); // kill_on_drop tears down the old process group
+                        drop(token); // give the slot back before reacquiring for the respawn
+                        token = ralph_jobserver().acquire().await?;
+                        child = self.spawn_loop_child(current_dir)?;
+                    }
+                    deadline = None;
+                }
+            }
+        }
+    }
+
+    /// Run `loop.sh` to completion, automatically restarting it (with
+    /// exponential backoff) if it crashes instead of converging on its own,
+    /// up to `policy.max_restarts` attempts. This is the executor-level
+    /// counterpart to `handle_ralph_process_failure`'s retry gate: that
+    /// gate operates above us, at the HTTP/job layer, retrying a whole
+    /// failed `ExecutionProcess` from scratch; this loop operates inside a
+    /// single `ExecutionProcess`, restarting the `loop.sh` child itself
+    /// without the overhead of re-entering task scheduling. The two don't
+    /// overlap: a crash exhausting `policy.max_restarts` here still
+    /// surfaces as one non-zero-exit `ExecutionProcess` for the outer gate
+    /// to retry or give up on.
+    ///
+    /// Each restart is its own process group, so the "circuit breaker" is
+    /// simply the `attempt` count bounding how many process groups this one
+    /// call will spawn - once it trips, the last failing exit status is
+    /// returned as-is instead of restarting again. Every restart also
+    /// pushes a `NormalizedEntry` noting the crash and the attempt count,
+    /// so the UI surfaces the retry instead of the run silently restarting
+    /// underneath it. Unlike `spawn_watched` (which restarts on file
+    /// changes), restarts here are driven by process exit.
+    pub async fn spawn_supervised(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        msg_store: Arc<MsgStore>,
+        policy: SupervisionPolicy,
+    ) -> Result<std::process::ExitStatus, ExecutorError> {
+        self.validate_setup(current_dir).await?;
+        if prompt.trim().is_empty() {
+            return Err(ExecutorError::Io(std::io::Error::other(
+                "Task must have a description (spec) to use Ralph",
+            )));
+        }
+        self.write_spec(current_dir, prompt).await?;
+
+        let index_provider = EntryIndexProvider::start_from(&msg_store);
+        let mut attempt = 0;
+
+        loop {
+            let token = ralph_jobserver().acquire().await?;
+            let mut child = self.spawn_loop_child(current_dir)?;
+            let status = child.wait().await.map_err(ExecutorError::Io)?;
+            drop(token);
+
+            if status.success() {
+                return Ok(status);
+            }
+
+            if attempt >= policy.max_restarts {
+                warn!(
+                    "Ralph supervised loop in {:?} crashed {} time(s); circuit breaker tripped, giving up",
+                    current_dir,
+                    attempt + 1
+                );
+                return Ok(status);
+            }
+
+            attempt += 1;
+            let delay = supervised_backoff_delay(attempt, policy.base_backoff, policy.max_backoff);
+            let message = format!(
+                "Ralph loop crashed (exit {:?}); restarting in {:?} (attempt {}/{})",
+                status.code(),
+                delay,
+                attempt,
+                policy.max_restarts
+            );
+            warn!("{}", message);
+            push_plan_entry(
+                &msg_store,
+                index_provider.clone(),
+                message,
+                Some(serde_json::json!({ "ralph_restart_attempt": attempt })),
+            );
+
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+/// Governs `RalphExecutor::spawn_supervised`'s automatic-restart behavior
+/// when `loop.sh` crashes (exits non-zero) instead of converging on its
+/// own.
+#[derive(Debug, Clone, Copy)]
+pub struct SupervisionPolicy {
+    /// How many times a crashed loop may be restarted before the circuit
+    /// breaker trips and the last failure is returned to the caller as-is.
+    pub max_restarts: u32,
+    pub base_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for SupervisionPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: 3,
+            base_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+/// `base * 2^attempt`, capped at `max_backoff`. Mirrors the shape of the
+/// retry gate's own `compute_backoff_delay`, computed locally since this
+/// runs a layer below it and has no access to that private helper.
+fn supervised_backoff_delay(attempt: u32, base: Duration, max_backoff: Duration) -> Duration {
+    base.saturating_mul(1u32 << attempt.min(20)).min(max_backoff)
 }
 
 #[async_trait]
@@ -124,7 +503,7 @@ impl StandardCodingAgentExecutor for RalphExecutor {
         info!("Ralph Executor: spawning in {} mode", mode_str);
 
         // 1. Validate Ralph setup
-        self.validate_setup(current_dir)?;
+        self.validate_setup(current_dir).await?;
 
         // 2. Validate we have a spec (prompt)
         if prompt.trim().is_empty() {
@@ -136,27 +515,11 @@ impl StandardCodingAgentExecutor for RalphExecutor {
         // 3. Write spec to file
         self.write_spec(current_dir, prompt).await?;
 
-        // 4. Build the command
-        let loop_script = self.loop_script_path(current_dir);
-        let mut cmd = tokio::process::Command::new(&loop_script);
-
-        // Add "plan" argument if in plan mode
-        if self.plan_mode {
-            cmd.arg("plan");
-        }
-
-        cmd.current_dir(current_dir)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .kill_on_drop(true);
-
-        debug!("Ralph: executing {:?}", cmd);
-
-        // 5. Spawn the process
-        let child = cmd.group_spawn().map_err(ExecutorError::Io)?;
+        // 4. Spawn the process
+        let child = self.spawn_loop_child(current_dir)?;
 
         info!("Ralph Executor: process spawned successfully");
-        Ok(SpawnedChild::from(child))
+        Ok(child)
     }
 
     async fn spawn_follow_up(
@@ -173,11 +536,29 @@ impl StandardCodingAgentExecutor for RalphExecutor {
         self.spawn(current_dir, prompt, env).await
     }
 
-    fn normalize_logs(&self, msg_store: Arc<MsgStore>, _worktree_path: &Path) {
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
         // Process stderr as error messages
         let entry_index_counter = EntryIndexProvider::start_from(&msg_store);
         normalize_stderr_logs(msg_store.clone(), entry_index_counter.clone());
 
+        // A `Json`-format plan run additionally writes a structured
+        // plan.json; wait for it in the background and turn it into one
+        // log entry per step, ordered by dependency, instead of leaving it
+        // as an opaque file the UI can't render. Only implemented for the
+        // local transport today - the file lives on the remote host
+        // otherwise, and polling that over SSH isn't worth it yet.
+        if self.plan_mode
+            && self.plan_format == PlanFormat::Json
+            && matches!(self.transport, ExecutionTransport::Local)
+        {
+            let plan_path = self.json_plan_path(worktree_path);
+            let plan_msg_store = msg_store.clone();
+            let plan_index_provider = entry_index_counter.clone();
+            tokio::spawn(async move {
+                emit_json_plan(plan_msg_store, plan_path, plan_index_provider).await;
+            });
+        }
+
         // Process stdout as assistant messages (plain text from Ralph loop)
         tokio::spawn(async move {
             use futures::StreamExt;
@@ -212,6 +593,153 @@ impl StandardCodingAgentExecutor for RalphExecutor {
     }
 }
 
+/// Wait for `plan.json` to appear (best-effort: a `Markdown`-format run, or
+/// a `loop.sh` that predates `--json-plan`, never writes one, and this
+/// simply gives up after `JSON_PLAN_POLL_TIMEOUT`), then parse it,
+/// topologically order the steps, and push one `NormalizedEntry` per step -
+/// or a single error entry if the file is malformed or its dependencies
+/// form a cycle.
+async fn emit_json_plan(
+    msg_store: Arc<MsgStore>,
+    plan_path: PathBuf,
+    index_provider: EntryIndexProvider,
+) {
+    let deadline = tokio::time::Instant::now() + JSON_PLAN_POLL_TIMEOUT;
+    loop {
+        if tokio::fs::try_exists(&plan_path).await.unwrap_or(false) {
+            break;
+        }
+        if tokio::time::Instant::now() >= deadline {
+            debug!("Ralph: timed out waiting for {:?}", plan_path);
+            return;
+        }
+        tokio::time::sleep(JSON_PLAN_POLL_INTERVAL).await;
+    }
+
+    let content = match tokio::fs::read_to_string(&plan_path).await {
+        Ok(content) => content,
+        Err(e) => {
+            warn!("Ralph: failed to read {:?}: {e}", plan_path);
+            return;
+        }
+    };
+
+    let steps: Vec<PlanStep> = match serde_json::from_str(&content) {
+        Ok(steps) => steps,
+        Err(e) => {
+            push_plan_entry(
+                &msg_store,
+                index_provider,
+                format!("Malformed JSON plan at {:?}: {e}", plan_path),
+                None,
+            );
+            return;
+        }
+    };
+
+    let order = match topo_sort_plan_steps(&steps) {
+        Ok(order) => order,
+        Err(message) => {
+            push_plan_entry(&msg_store, index_provider, message, None);
+            return;
+        }
+    };
+
+    for step_index in order {
+        let step = &steps[step_index];
+        let metadata = serde_json::json!({
+            "id": step.id,
+            "depends_on": step.depends_on,
+            "files": step.files,
+        });
+        push_plan_entry(
+            &msg_store,
+            index_provider.clone(),
+            format!("{}\n\n{}", step.title, step.description),
+            Some(metadata),
+        );
+    }
+}
+
+/// Topologically order `steps` by `id`/`depends_on`, flattening layers of
+/// independent steps in declaration order. Mirrors
+/// `RalphDagRequest::topological_layers`, but over plan steps instead of
+/// spec nodes. `depends_on` entries that don't match any step's `id` are
+/// ignored rather than treated as an error.
+fn topo_sort_plan_steps(steps: &[PlanStep]) -> Result<Vec<usize>, String> {
+    let index_by_id: std::collections::HashMap<&str, usize> =
+        steps.iter().enumerate().map(|(i, step)| (step.id.as_str(), i)).collect();
+
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); steps.len()];
+    let mut remaining: Vec<usize> = vec![0; steps.len()];
+
+    for (i, step) in steps.iter().enumerate() {
+        for dep in &step.depends_on {
+            if let Some(&dep_index) = index_by_id.get(dep.as_str()) {
+                remaining[i] += 1;
+                dependents[dep_index].push(i);
+            }
+        }
+    }
+
+    let mut scheduled = vec![false; steps.len()];
+    let mut order = Vec::with_capacity(steps.len());
+
+    loop {
+        let layer: Vec<usize> =
+            (0..steps.len()).filter(|&i| !scheduled[i] && remaining[i] == 0).collect();
+
+        if layer.is_empty() {
+            break;
+        }
+
+        for i in layer {
+            scheduled[i] = true;
+            order.push(i);
+            for &dependent in &dependents[i] {
+                remaining[dependent] -= 1;
+            }
+        }
+    }
+
+    if order.len() != steps.len() {
+        let cycle: Vec<&str> = (0..steps.len())
+            .filter(|&i| !scheduled[i])
+            .map(|i| steps[i].id.as_str())
+            .collect();
+        return Err(format!(
+            "JSON plan has a dependency cycle among steps: {}",
+            cycle.join(", ")
+        ));
+    }
+
+    Ok(order)
+}
+
+/// Push a single `NormalizedEntry` through the same `PlainTextLogProcessor`
+/// machinery the plain-text stdout pump uses, so its index stays consistent
+/// with the rest of the run's log stream.
+fn push_plan_entry(
+    msg_store: &Arc<MsgStore>,
+    index_provider: EntryIndexProvider,
+    content: String,
+    metadata: Option<serde_json::Value>,
+) {
+    let mut processor = PlainTextLogProcessor::builder()
+        .normalized_entry_producer(Box::new(move |content: String| NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::AssistantMessage,
+            content,
+            metadata: metadata.clone(),
+        }))
+        .index_provider(index_provider)
+        .build();
+
+    for patch in processor.process(content) {
+        msg_store.push_patch(patch);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -241,10 +769,6 @@ mod tests {
             executor.loop_script_path(worktree),
             Path::new("/tmp/test-worktree/.ralph-vibe-kanban/loop.sh")
         );
-        assert_eq!(
-            executor.spec_path(worktree),
-            Path::new("/tmp/test-worktree/.ralph-vibe-kanban/spec")
-        );
     }
 
     #[test]
@@ -254,4 +778,130 @@ mod tests {
         // This is a safe default - you must explicitly set plan_mode to true
         assert!(!executor.plan_mode);
     }
+
+    #[test]
+    fn test_with_watch_enables_watch_and_sets_debounce() {
+        let executor = RalphExecutor::build().with_watch(500);
+        assert!(executor.watch);
+        assert_eq!(executor.debounce_ms, 500);
+    }
+
+    #[test]
+    fn test_debounce_falls_back_to_default_when_unset() {
+        let executor = RalphExecutor::default();
+        assert_eq!(executor.debounce(), Duration::from_millis(DEFAULT_DEBOUNCE_MS));
+
+        let executor = RalphExecutor::default().with_watch(750);
+        assert_eq!(executor.debounce(), Duration::from_millis(750));
+    }
+
+    #[test]
+    fn test_watch_ignore_always_excludes_ralph_and_git_dirs() {
+        let executor = RalphExecutor::default();
+        let worktree = std::env::temp_dir();
+        let ignore = executor.build_watch_ignore(&worktree);
+
+        assert!(ignore
+            .matched_path_or_any_parents(worktree.join(".ralph-vibe-kanban").join("spec"), false)
+            .is_ignore());
+        assert!(ignore
+            .matched_path_or_any_parents(worktree.join(".git").join("HEAD"), false)
+            .is_ignore());
+        assert!(!ignore
+            .matched_path_or_any_parents(worktree.join("IMPLEMENTATION_PLAN.md"), false)
+            .is_ignore());
+    }
+
+    fn step(id: &str, depends_on: &[&str]) -> PlanStep {
+        PlanStep {
+            id: id.to_string(),
+            title: format!("Step {id}"),
+            description: String::new(),
+            depends_on: depends_on.iter().map(|s| s.to_string()).collect(),
+            files: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_topo_sort_plan_steps_orders_by_dependency() {
+        let steps = vec![step("c", &["b"]), step("a", &[]), step("b", &["a"])];
+        let order = topo_sort_plan_steps(&steps).unwrap();
+        let ids: Vec<&str> = order.iter().map(|&i| steps[i].id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_topo_sort_plan_steps_ignores_unknown_dependency() {
+        let steps = vec![step("a", &["does-not-exist"])];
+        let order = topo_sort_plan_steps(&steps).unwrap();
+        assert_eq!(order, vec![0]);
+    }
+
+    #[test]
+    fn test_topo_sort_plan_steps_rejects_cycle() {
+        let steps = vec![step("a", &["b"]), step("b", &["a"])];
+        let err = topo_sort_plan_steps(&steps).unwrap_err();
+        assert!(err.contains('a') && err.contains('b'));
+    }
+
+    #[test]
+    fn test_build_loop_command_passes_json_plan_flag_only_for_json_plan_mode() {
+        let executor = RalphExecutor::plan();
+        let cmd = executor.build_loop_command(Path::new("/tmp/test-worktree"));
+        assert!(!format!("{cmd:?}").contains("--json-plan"));
+
+        let executor = RalphExecutor::plan();
+        let mut json_executor = executor;
+        json_executor.plan_format = PlanFormat::Json;
+        let cmd = json_executor.build_loop_command(Path::new("/tmp/test-worktree"));
+        assert!(format!("{cmd:?}").contains("--json-plan"));
+
+        let mut build_executor = RalphExecutor::build();
+        build_executor.plan_format = PlanFormat::Json;
+        let cmd = build_executor.build_loop_command(Path::new("/tmp/test-worktree"));
+        assert!(!format!("{cmd:?}").contains("--json-plan"));
+    }
+
+    #[test]
+    fn test_supervision_policy_default_allows_a_few_restarts() {
+        let policy = SupervisionPolicy::default();
+        assert_eq!(policy.max_restarts, 3);
+        assert!(policy.base_backoff < policy.max_backoff);
+    }
+
+    #[test]
+    fn test_supervised_backoff_delay_doubles_then_caps() {
+        let base = Duration::from_secs(1);
+        let max_backoff = Duration::from_secs(10);
+
+        assert_eq!(supervised_backoff_delay(1, base, max_backoff), Duration::from_secs(2));
+        assert_eq!(supervised_backoff_delay(2, base, max_backoff), Duration::from_secs(4));
+        assert_eq!(supervised_backoff_delay(5, base, max_backoff), max_backoff);
+    }
+
+    #[test]
+    fn test_ralph_jobserver_is_a_shared_singleton() {
+        let first = ralph_jobserver();
+        let second = ralph_jobserver();
+        assert!(Arc::ptr_eq(first, second));
+    }
+
+    #[tokio::test]
+    async fn test_ralph_jobserver_gates_concurrent_acquisitions() {
+        // Acquire every token the shared pool has (parallelism - 1, since the
+        // caller's own slot is implicit) and confirm the next acquire blocks
+        // until one is released, the same guarantee `spawn_watched`/
+        // `spawn_supervised` rely on to bound concurrent loops.
+        let mut held = Vec::new();
+        for _ in 0..(DEFAULT_JOBSERVER_PARALLELISM - 1) {
+            held.push(ralph_jobserver().acquire().await.unwrap());
+        }
+
+        let exhausted = tokio::time::timeout(Duration::from_millis(50), ralph_jobserver().acquire()).await;
+        assert!(exhausted.is_err(), "acquire should not resolve once the shared pool is exhausted");
+
+        held.pop();
+        let now_available = tokio::time::timeout(Duration::from_millis(200), ralph_jobserver().acquire()).await;
+        assert!(now_available.is_ok(), "dropping a held token should free a slot for the next acquire");
+    }
 }
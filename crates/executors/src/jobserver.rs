@@ -0,0 +1,194 @@
+//! GNU-make-compatible jobserver for bounding concurrent agent loops.
+//!
+//! A pipe is preloaded with `parallelism - 1` single-byte tokens (the slot
+//! held by the loop that creates the pool is implicit and never written to
+//! the pipe). Before spawning, a loop acquires a token by reading one byte;
+//! it releases the token by writing the byte back when its child exits. The
+//! read/write fds are exported to the child via `MAKEFLAGS`/
+//! `--jobserver-auth=<r>,<w>`, so any `make`/`cargo` it invokes shares the
+//! same pool and total concurrency stays bounded across the whole tree.
+
+use std::{
+    os::fd::{AsRawFd, BorrowedFd, OwnedFd, RawFd},
+    sync::Arc,
+};
+
+use nix::{
+    fcntl::{fcntl, FcntlArg, OFlag},
+    unistd,
+};
+
+use crate::executors::ExecutorError;
+
+/// Shared pool of tokens bounding how many agent loops (and their child
+/// toolchains) may run concurrently.
+#[derive(Debug)]
+pub struct Jobserver {
+    read_fd: OwnedFd,
+    write_fd: OwnedFd,
+}
+
+/// A held token. Returned to the pool on drop, so acquisition is safe to use
+/// with `kill_on_drop`-style cancellation: losing the token's owner (error,
+/// panic, or the loop's drop) always gives the slot back.
+pub struct JobToken {
+    jobserver: Arc<Jobserver>,
+}
+
+impl Jobserver {
+    /// Create a pool with `parallelism` total slots. One slot is implicit and
+    /// is never placed in the pipe; the remaining `parallelism - 1` tokens
+    /// are pre-loaded so they can be acquired by concurrent loops.
+    pub fn new(parallelism: u32) -> Result<Arc<Self>, ExecutorError> {
+        let (read_fd, write_fd) = unistd::pipe().map_err(std::io::Error::from).map_err(ExecutorError::Io)?;
+
+        // `acquire` parks this fd on `AsyncFd` and reads it via `try_io`,
+        // which requires a real EAGAIN on an empty pipe instead of blocking
+        // the tokio worker thread outright.
+        let flags = fcntl(&read_fd, FcntlArg::F_GETFL).map_err(std::io::Error::from).map_err(ExecutorError::Io)?;
+        fcntl(
+            &read_fd,
+            FcntlArg::F_SETFL(OFlag::from_bits_truncate(flags) | OFlag::O_NONBLOCK),
+        )
+        .map_err(std::io::Error::from)
+        .map_err(ExecutorError::Io)?;
+
+        let jobserver = Self { read_fd, write_fd };
+
+        for _ in 0..parallelism.saturating_sub(1) {
+            jobserver.write_token()?;
+        }
+
+        Ok(Arc::new(jobserver))
+    }
+
+    /// `MAKEFLAGS` value advertising this pool's jobserver auth, to be
+    /// applied through `ExecutionEnv` so a spawned loop (and anything it
+    /// shells out to) can join the same pool.
+    pub fn make_flags(&self) -> String {
+        format!(
+            "--jobserver-auth={},{}",
+            self.read_fd.as_raw_fd(),
+            self.write_fd.as_raw_fd()
+        )
+    }
+
+    pub fn read_fd(&self) -> RawFd {
+        self.read_fd.as_raw_fd()
+    }
+
+    pub fn write_fd(&self) -> RawFd {
+        self.write_fd.as_raw_fd()
+    }
+
+    /// Acquire one token, asynchronously waiting until one is available.
+    pub async fn acquire(self: &Arc<Self>) -> Result<JobToken, ExecutorError> {
+        let fd = self.read_fd.as_raw_fd();
+        let async_fd = tokio::io::unix::AsyncFd::new(BorrowedRawFd(fd)).map_err(ExecutorError::Io)?;
+
+        loop {
+            let mut guard = async_fd.readable().await.map_err(ExecutorError::Io)?;
+            match guard.try_io(|_| read_one_byte(fd)) {
+                Ok(Ok(())) => {
+                    return Ok(JobToken {
+                        jobserver: Arc::clone(self),
+                    });
+                }
+                Ok(Err(e)) => return Err(ExecutorError::Io(e)),
+                Err(_would_block) => continue,
+            }
+        }
+    }
+
+    fn write_token(&self) -> Result<(), ExecutorError> {
+        write_one_byte(self.write_fd.as_raw_fd()).map_err(ExecutorError::Io)
+    }
+}
+
+/// A non-owning handle used only to park the read fd on `AsyncFd` without
+/// closing it when the `AsyncFd` wrapper is dropped after each poll.
+struct BorrowedRawFd(RawFd);
+
+impl AsRawFd for BorrowedRawFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+fn read_one_byte(fd: RawFd) -> std::io::Result<()> {
+    let mut buf = [0u8; 1];
+    // SAFETY: `fd` is borrowed from an `OwnedFd` kept alive by `Jobserver`
+    // for the duration of this call; it's never closed here.
+    let borrowed = unsafe { BorrowedFd::borrow_raw(fd) };
+    match unistd::read(borrowed, &mut buf) {
+        Ok(1) => Ok(()),
+        Ok(_) => Err(std::io::Error::other("jobserver pipe closed")),
+        Err(nix::errno::Errno::EAGAIN) => Err(std::io::ErrorKind::WouldBlock.into()),
+        Err(e) => Err(std::io::Error::from(e)),
+    }
+}
+
+fn write_one_byte(fd: RawFd) -> std::io::Result<()> {
+    // SAFETY: see `read_one_byte`.
+    let borrowed = unsafe { BorrowedFd::borrow_raw(fd) };
+    unistd::write(borrowed, b"+").map(|_| ()).map_err(std::io::Error::from)
+}
+
+impl Drop for JobToken {
+    fn drop(&mut self) {
+        // Best-effort: give the token back. If the pipe is gone the pool is
+        // being torn down anyway.
+        let _ = self.jobserver.write_token();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_new_preloads_parallelism_minus_one_tokens() {
+        let pool = Jobserver::new(3).unwrap();
+
+        // The slot held by the loop that creates the pool is implicit and
+        // never queued, so `parallelism - 1` = 2 tokens should be
+        // immediately available.
+        let _first = pool.acquire().await.unwrap();
+        let _second = pool.acquire().await.unwrap();
+
+        // The pool is now exhausted; a third acquire should not resolve.
+        let third = tokio::time::timeout(std::time::Duration::from_millis(50), pool.acquire()).await;
+        assert!(third.is_err(), "acquire should not resolve once the pool is exhausted");
+    }
+
+    #[tokio::test]
+    async fn test_token_is_returned_to_pool_on_drop() {
+        let pool = Jobserver::new(2).unwrap();
+
+        let first = pool.acquire().await.unwrap();
+        drop(first);
+
+        // Dropping the token should have written it back to the pipe.
+        let second = tokio::time::timeout(std::time::Duration::from_millis(200), pool.acquire()).await;
+        assert!(second.is_ok(), "token should be returned to the pool on drop");
+    }
+
+    #[tokio::test]
+    async fn test_single_slot_pool_starts_exhausted() {
+        // parallelism - 1 = 0 tokens preloaded, since the creator's own slot
+        // is implicit.
+        let pool = Jobserver::new(1).unwrap();
+
+        let acquired = tokio::time::timeout(std::time::Duration::from_millis(50), pool.acquire()).await;
+        assert!(acquired.is_err());
+    }
+
+    #[test]
+    fn test_make_flags_reports_the_pipe_fds() {
+        let pool = Jobserver::new(2).unwrap();
+        assert_eq!(
+            pool.make_flags(),
+            format!("--jobserver-auth={},{}", pool.read_fd(), pool.write_fd())
+        );
+    }
+}
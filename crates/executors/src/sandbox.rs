@@ -0,0 +1,327 @@
+//! Namespace + overlayfs sandboxing for a single loop iteration.
+//!
+//! When enabled, a loop runs against a copy-on-write view of the project
+//! instead of the real working tree: the project directory is mounted
+//! read-only as the overlay's lower layer, a fresh scratch directory is the
+//! writable upper layer, and the child is `unshare`d into new mount/user
+//! namespaces and `chdir`ed into the merged view before it execs `loop.sh`.
+//! Once the process group exits, the upper layer (the set of changed files)
+//! is either committed back onto the real tree or discarded, giving callers
+//! per-run rollback.
+//!
+//! `unshare(CLONE_NEWUSER)` fails with `EINVAL` in a multi-threaded process,
+//! which every tokio binary is, so the namespace/mount setup can't happen in
+//! this (or any other) async-runtime thread. Instead `apply_to_command`
+//! attaches it as a `pre_exec` hook: that closure runs in the freshly forked
+//! child right before `execve`, which is always single-threaded regardless
+//! of the parent, and since namespaces persist across `execve` the overlay
+//! mount it creates is guaranteed visible to the child that's about to run.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::executors::ExecutorError;
+
+/// Opt-in sandboxing for a `RalphLoopRequest` iteration.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, Default)]
+pub enum SandboxPolicy {
+    /// Run directly against the real working tree (current behavior).
+    #[default]
+    None,
+    /// Run against an overlayfs view; `commit_on_success` controls whether
+    /// the upper layer is merged back onto the real tree when the process
+    /// group exits with a zero status.
+    Overlay { commit_on_success: bool },
+}
+
+/// A prepared (but not yet entered) overlay mount for one sandboxed run.
+/// The namespace and mount themselves aren't created until
+/// `apply_to_command` attaches them to the child that will exec into them.
+pub struct SandboxSession {
+    /// The directory the child is `chdir`ed into once its overlay mount is
+    /// live.
+    pub merged_dir: PathBuf,
+    lower_dir: PathBuf,
+    upper_dir: PathBuf,
+    work_dir: PathBuf,
+    scratch_root: PathBuf,
+    commit_on_success: bool,
+    #[cfg(target_os = "linux")]
+    uid: u32,
+    #[cfg(target_os = "linux")]
+    gid: u32,
+}
+
+/// The outcome of tearing down a `SandboxSession`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SandboxResult {
+    /// Files the iteration touched (added, modified, or removed), relative
+    /// to the project root, surfaced so the UI can show a per-run diff.
+    pub changed_files: Vec<String>,
+    /// Whether the upper layer was merged back onto the real tree.
+    pub committed: bool,
+}
+
+impl SandboxSession {
+    /// Lay out the scratch directories for an overlay view of `project_dir`.
+    /// Linux-only; on any other platform this returns a typed error rather
+    /// than silently running unsandboxed. Doesn't touch namespaces or mounts
+    /// itself — see `apply_to_command` — so on Linux, unavailable
+    /// namespaces/overlayfs surface as an error when the command is spawned.
+    #[cfg(target_os = "linux")]
+    pub async fn setup(
+        project_dir: &Path,
+        commit_on_success: bool,
+    ) -> Result<Self, ExecutorError> {
+        use nix::unistd::{getgid, getuid};
+
+        let scratch_root = std::env::temp_dir().join(format!("ralph-sandbox-{}", Uuid::new_v4()));
+        let upper_dir = scratch_root.join("upper");
+        let work_dir = scratch_root.join("work");
+        let merged_dir = scratch_root.join("merged");
+
+        for dir in [&upper_dir, &work_dir, &merged_dir] {
+            tokio::fs::create_dir_all(dir).await.map_err(ExecutorError::Io)?;
+        }
+
+        Ok(Self {
+            merged_dir,
+            lower_dir: project_dir.to_path_buf(),
+            upper_dir,
+            work_dir,
+            scratch_root,
+            commit_on_success,
+            uid: getuid().as_raw(),
+            gid: getgid().as_raw(),
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub async fn setup(_project_dir: &Path, _commit_on_success: bool) -> Result<Self, ExecutorError> {
+        Err(ExecutorError::Io(std::io::Error::other(
+            "sandboxed loops require Linux namespaces + overlayfs, which aren't available on this platform",
+        )))
+    }
+
+    /// Attach the namespace entry + overlay mount to `command` as a
+    /// `pre_exec` hook, so they happen in the child that's about to exec
+    /// `loop.sh` rather than in this (multi-threaded) process. The child is
+    /// left `chdir`ed into `merged_dir` once the hook runs, so `command`
+    /// doesn't need its own `current_dir` call.
+    #[cfg(target_os = "linux")]
+    pub fn apply_to_command(&self, command: &mut Command) {
+        let lower_dir = self.lower_dir.clone();
+        let upper_dir = self.upper_dir.clone();
+        let work_dir = self.work_dir.clone();
+        let merged_dir = self.merged_dir.clone();
+        let uid = self.uid;
+        let gid = self.gid;
+
+        // SAFETY: this closure runs alone in the freshly forked child,
+        // before exec, so calling these syscalls directly here is safe.
+        unsafe {
+            command.pre_exec(move || {
+                enter_sandbox_namespace(&lower_dir, &upper_dir, &work_dir, &merged_dir, uid, gid)
+            });
+        }
+    }
+
+    /// Unreachable in practice: `setup` already fails on non-Linux targets,
+    /// so no `SandboxSession` ever exists to call this on. Exists only so
+    /// callers don't need to cfg-gate the call site.
+    #[cfg(not(target_os = "linux"))]
+    pub fn apply_to_command(&self, _command: &mut Command) {}
+
+    /// Remove the scratch directories, committing or discarding the upper
+    /// layer per policy and `succeeded`. No unmount happens here: the overlay
+    /// mount exists only in the child's own mount namespace. Always runs
+    /// the cleanup, even when `kill_on_drop` tore the process down early.
+    pub async fn teardown(self, succeeded: bool) -> Result<SandboxResult, ExecutorError> {
+        let changed_files = collect_changed_files(&self.upper_dir).await?;
+        let committed = self.commit_on_success && succeeded;
+
+        if committed {
+            for rel in &changed_files {
+                let from = self.upper_dir.join(rel);
+                let to = self.lower_dir.join(rel);
+                if let Some(parent) = to.parent() {
+                    tokio::fs::create_dir_all(parent).await.map_err(ExecutorError::Io)?;
+                }
+                tokio::fs::copy(&from, &to).await.map_err(ExecutorError::Io)?;
+            }
+        }
+
+        tokio::fs::remove_dir_all(&self.scratch_root)
+            .await
+            .map_err(ExecutorError::Io)?;
+
+        Ok(SandboxResult {
+            changed_files,
+            committed,
+        })
+    }
+}
+
+impl Drop for SandboxSession {
+    /// Best-effort synchronous cleanup for the case where this session is
+    /// dropped without `teardown` ever running to completion - e.g. the
+    /// enclosing future is cancelled while awaiting `child.wait()`, the same
+    /// way `kill_on_drop` tears down the child process itself. `teardown`
+    /// already removes `scratch_root` on the happy path, so by the time its
+    /// `self` goes out of scope this is a no-op; failures (most commonly
+    /// "already removed") are ignored since there's no async caller left to
+    /// hand an error to.
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.scratch_root);
+    }
+}
+
+/// Runs inside the forked child, before `execve`: enters new mount + user
+/// namespaces, maps the caller's uid/gid into them, mounts the overlay, and
+/// `chdir`s into the merged view. Deliberately doesn't `unshare(CLONE_NEWPID)`
+/// — that only applies to processes this one forks *after* the call, not to
+/// the process itself, so it would isolate nothing for a child that's about
+/// to `exec` in place rather than fork again.
+#[cfg(target_os = "linux")]
+fn enter_sandbox_namespace(
+    lower_dir: &Path,
+    upper_dir: &Path,
+    work_dir: &Path,
+    merged_dir: &Path,
+    uid: u32,
+    gid: u32,
+) -> std::io::Result<()> {
+    use nix::{
+        mount::{MsFlags, mount},
+        sched::{CloneFlags, unshare},
+        unistd::chdir,
+    };
+
+    unshare(CloneFlags::CLONE_NEWNS | CloneFlags::CLONE_NEWUSER)
+        .map_err(|e| std::io::Error::other(format!("unshare: {e}")))?;
+
+    write_id_map("/proc/self/uid_map", uid, uid)?;
+    std::fs::write("/proc/self/setgroups", "deny")?;
+    write_id_map("/proc/self/gid_map", gid, gid)?;
+
+    let options = format!(
+        "lowerdir={},upperdir={},workdir={}",
+        lower_dir.display(),
+        upper_dir.display(),
+        work_dir.display()
+    );
+    mount(
+        Some("overlay"),
+        merged_dir,
+        Some("overlay"),
+        MsFlags::empty(),
+        Some(options.as_str()),
+    )
+    .map_err(|e| std::io::Error::other(format!("overlay mount: {e}")))?;
+
+    chdir(merged_dir).map_err(|e| std::io::Error::other(format!("chdir: {e}")))?;
+
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn write_id_map(path: &str, inside: u32, outside: u32) -> std::io::Result<()> {
+    std::fs::write(path, format!("{inside} {outside} 1"))
+}
+
+async fn collect_changed_files(upper_dir: &Path) -> Result<Vec<String>, ExecutorError> {
+    let mut changed = Vec::new();
+    let mut stack = vec![upper_dir.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        let mut entries = tokio::fs::read_dir(&dir).await.map_err(ExecutorError::Io)?;
+        while let Some(entry) = entries.next_entry().await.map_err(ExecutorError::Io)? {
+            let path = entry.path();
+            if entry.file_type().await.map_err(ExecutorError::Io)?.is_dir() {
+                stack.push(path);
+            } else if let Ok(rel) = path.strip_prefix(upper_dir) {
+                changed.push(rel.to_string_lossy().to_string());
+            }
+        }
+    }
+
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sandbox_policy_defaults_to_none() {
+        assert_eq!(SandboxPolicy::default(), SandboxPolicy::None);
+    }
+
+    #[tokio::test]
+    async fn test_collect_changed_files_lists_nested_paths_relative_to_upper_dir() {
+        let upper_dir = std::env::temp_dir().join(format!("sandbox-test-{}", Uuid::new_v4()));
+        tokio::fs::create_dir_all(upper_dir.join("src/nested"))
+            .await
+            .unwrap();
+        tokio::fs::write(upper_dir.join("top.txt"), b"top").await.unwrap();
+        tokio::fs::write(upper_dir.join("src/nested/deep.txt"), b"deep")
+            .await
+            .unwrap();
+
+        let mut changed = collect_changed_files(&upper_dir).await.unwrap();
+        changed.sort();
+
+        tokio::fs::remove_dir_all(&upper_dir).await.unwrap();
+
+        assert_eq!(
+            changed,
+            vec!["src/nested/deep.txt".to_string(), "top.txt".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_collect_changed_files_is_empty_for_an_untouched_upper_dir() {
+        let upper_dir = std::env::temp_dir().join(format!("sandbox-test-{}", Uuid::new_v4()));
+        tokio::fs::create_dir_all(&upper_dir).await.unwrap();
+
+        let changed = collect_changed_files(&upper_dir).await.unwrap();
+
+        tokio::fs::remove_dir_all(&upper_dir).await.unwrap();
+
+        assert!(changed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dropping_a_session_without_teardown_still_removes_scratch_root() {
+        let scratch_root = std::env::temp_dir().join(format!("sandbox-drop-test-{}", Uuid::new_v4()));
+        let upper_dir = scratch_root.join("upper");
+        let work_dir = scratch_root.join("work");
+        let merged_dir = scratch_root.join("merged");
+        for dir in [&upper_dir, &work_dir, &merged_dir] {
+            tokio::fs::create_dir_all(dir).await.unwrap();
+        }
+
+        let session = SandboxSession {
+            merged_dir,
+            lower_dir: std::env::temp_dir(),
+            upper_dir,
+            work_dir,
+            scratch_root: scratch_root.clone(),
+            commit_on_success: false,
+            #[cfg(target_os = "linux")]
+            uid: 0,
+            #[cfg(target_os = "linux")]
+            gid: 0,
+        };
+
+        // Simulate the enclosing future being cancelled before `teardown`
+        // runs, e.g. while still awaiting `child.wait()`.
+        drop(session);
+
+        assert!(!scratch_root.exists());
+    }
+}
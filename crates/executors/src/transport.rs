@@ -0,0 +1,231 @@
+//! Pluggable execution transport: run a command against the worktree
+//! directly, or against the same worktree mirrored onto a remote host over
+//! an SSH tunnel, without the caller (an executor's `spawn`) needing to
+//! care which.
+//!
+//! The remote path shells out to the system `ssh` client exactly the way
+//! `RalphExecutor`/`RalphLoopRequest` already shell out to `loop.sh` -
+//! no SSH library dependency, and it composes with the existing
+//! `kill_on_drop` convention for free: `ssh -tt` allocates a remote pty, so
+//! losing the local client process (however it dies - `kill_on_drop`,
+//! `Drop`, a signal) sends SIGHUP to the remote foreground process, same as
+//! a disconnected terminal would. There's no separate "remote process
+//! group" to track; the local `ssh` child already stands in for it.
+
+use std::path::Path;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::{io::AsyncWriteExt, process::Command};
+use ts_rs::TS;
+
+use crate::executors::ExecutorError;
+
+/// Where and how to reach a remote host over SSH.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct RemoteConnect {
+    /// `user@host` (or bare `host`), exactly as passed to `ssh`.
+    pub host: String,
+    /// Extra `ssh` arguments, e.g. `["-p", "2222", "-i", "~/.ssh/id_ed25519"]`.
+    #[serde(default)]
+    pub ssh_args: Vec<String>,
+    /// Absolute path to the worktree on the remote host. The caller is
+    /// responsible for having synced the worktree there before spawning.
+    pub remote_path: String,
+}
+
+/// Where a command should actually run.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, TS, JsonSchema)]
+pub enum ExecutionTransport {
+    /// Run directly against the local filesystem (today's behavior).
+    #[default]
+    Local,
+    /// Run on a remote host over an SSH tunnel.
+    Remote(RemoteConnect),
+}
+
+impl ExecutionTransport {
+    /// Build the command that actually runs `relative_program` (a path
+    /// relative to the worktree, e.g. `.ralph-vibe-kanban/loop.sh`) with
+    /// `args`. Output is relayed back to this process's pipes either way -
+    /// directly for `Local`, over the SSH channel for `Remote`. Under
+    /// `Remote` the forced pty (`-tt`) merges the remote stderr into the
+    /// local stdout, so only `ssh`'s own messages reach the local stderr.
+    /// The caller still owns `stdout`/`stderr`/`kill_on_drop`/
+    /// spawning, matching every other command built in this crate.
+    pub fn build_command(
+        &self,
+        current_dir: &Path,
+        relative_program: &str,
+        args: &[String],
+    ) -> Command {
+        match self {
+            ExecutionTransport::Local => {
+                let mut cmd = Command::new(current_dir.join(relative_program));
+                cmd.args(args).current_dir(current_dir);
+                cmd
+            }
+            ExecutionTransport::Remote(connect) => {
+                let mut cmd = Command::new("ssh");
+                // Force a remote pty: this is what makes losing the local
+                // ssh client (e.g. via kill_on_drop) tear down the remote
+                // foreground process too, instead of leaving it orphaned.
+                cmd.arg("-tt");
+                cmd.args(&connect.ssh_args);
+                cmd.arg(&connect.host);
+                cmd.arg(remote_shell_command(connect, relative_program, args));
+                cmd
+            }
+        }
+    }
+
+    /// Does `relative_path` exist under the worktree (local) or under
+    /// `remote_path` (remote)? Used in place of `Path::exists` wherever
+    /// setup validation needs to work for either transport.
+    pub async fn path_exists(&self, current_dir: &Path, relative_path: &str) -> bool {
+        match self {
+            ExecutionTransport::Local => current_dir.join(relative_path).exists(),
+            ExecutionTransport::Remote(connect) => {
+                let remote_path = join_remote(&connect.remote_path, relative_path);
+                Command::new("ssh")
+                    .args(&connect.ssh_args)
+                    .arg(&connect.host)
+                    .arg(format!("test -e {}", shell_quote(&remote_path)))
+                    .status()
+                    .await
+                    .map(|status| status.success())
+                    .unwrap_or(false)
+            }
+        }
+    }
+
+    /// Write `content` to `relative_path` under the worktree (local) or
+    /// under `remote_path` (remote, via `ssh ... 'cat > path'` piped over
+    /// stdin rather than a separate `scp`/`sftp` round-trip).
+    pub async fn write_file(
+        &self,
+        current_dir: &Path,
+        relative_path: &str,
+        content: &str,
+    ) -> Result<(), ExecutorError> {
+        match self {
+            ExecutionTransport::Local => tokio::fs::write(current_dir.join(relative_path), content)
+                .await
+                .map_err(ExecutorError::Io),
+            ExecutionTransport::Remote(connect) => {
+                let remote_path = join_remote(&connect.remote_path, relative_path);
+                let mut child = Command::new("ssh")
+                    .args(&connect.ssh_args)
+                    .arg(&connect.host)
+                    .arg(format!("cat > {}", shell_quote(&remote_path)))
+                    .stdin(std::process::Stdio::piped())
+                    .spawn()
+                    .map_err(ExecutorError::Io)?;
+
+                let mut stdin = child.stdin.take().expect("stdin was piped above");
+                stdin.write_all(content.as_bytes()).await.map_err(ExecutorError::Io)?;
+                drop(stdin);
+
+                let status = child.wait().await.map_err(ExecutorError::Io)?;
+                if !status.success() {
+                    return Err(ExecutorError::Io(std::io::Error::other(format!(
+                        "Failed to write {relative_path} on {}",
+                        connect.host
+                    ))));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Join a worktree-relative path onto a remote base path with `/`,
+/// regardless of whether `base` already ends in one.
+fn join_remote(base: &str, relative_path: &str) -> String {
+    format!("{}/{relative_path}", base.trim_end_matches('/'))
+}
+
+/// Build the `cd <remote_path> && <program> <args...>` string `ssh` runs on
+/// the far side, single-quoting every path/argument.
+fn remote_shell_command(connect: &RemoteConnect, relative_program: &str, args: &[String]) -> String {
+    let program = join_remote(&connect.remote_path, relative_program);
+    let remote_path = connect.remote_path.trim_end_matches('/');
+    let mut command = format!("cd {} && {}", shell_quote(remote_path), shell_quote(&program));
+    for arg in args {
+        command.push(' ');
+        command.push_str(&shell_quote(arg));
+    }
+    command
+}
+
+/// Minimal POSIX single-quote escaping, sufficient for the paths/args this
+/// module builds into a remote shell command.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connect() -> RemoteConnect {
+        RemoteConnect {
+            host: "build-box".to_string(),
+            ssh_args: vec!["-p".to_string(), "2222".to_string()],
+            remote_path: "/home/ralph/worktree/".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_shell_quote_escapes_single_quotes() {
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+    }
+
+    #[test]
+    fn test_join_remote_handles_trailing_slash() {
+        assert_eq!(join_remote("/a/b/", "c/d"), "/a/b/c/d");
+        assert_eq!(join_remote("/a/b", "c/d"), "/a/b/c/d");
+    }
+
+    #[test]
+    fn test_remote_shell_command_cds_and_quotes() {
+        let command = remote_shell_command(
+            &connect(),
+            ".ralph-vibe-kanban/loop.sh",
+            &["plan".to_string()],
+        );
+        assert_eq!(
+            command,
+            "cd '/home/ralph/worktree' && '/home/ralph/worktree/.ralph-vibe-kanban/loop.sh' 'plan'"
+        );
+    }
+
+    #[test]
+    fn test_build_command_local_uses_program_under_current_dir() {
+        let transport = ExecutionTransport::Local;
+        let cmd = transport.build_command(Path::new("/tmp/wt"), ".ralph-vibe-kanban/loop.sh", &[]);
+        assert_eq!(
+            cmd.as_std().get_program(),
+            Path::new("/tmp/wt/.ralph-vibe-kanban/loop.sh")
+        );
+    }
+
+    #[test]
+    fn test_build_command_remote_invokes_ssh_with_pty_flag() {
+        let transport = ExecutionTransport::Remote(connect());
+        let cmd = transport.build_command(Path::new("/tmp/wt"), ".ralph-vibe-kanban/loop.sh", &[]);
+        let std_cmd = cmd.as_std();
+        assert_eq!(std_cmd.get_program(), "ssh");
+        let args: Vec<String> = std_cmd
+            .get_args()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(args[0], "-tt");
+        assert!(args.contains(&"build-box".to_string()));
+    }
+
+    #[test]
+    fn test_default_transport_is_local() {
+        assert_eq!(ExecutionTransport::default(), ExecutionTransport::Local);
+    }
+}
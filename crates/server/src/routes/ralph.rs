@@ -7,12 +7,17 @@
 
 use axum::{
     Extension, Router,
-    extract::State,
-    http::StatusCode,
+    body::Bytes,
+    extract::{Path as AxumPath, Query, State},
+    http::{HeaderMap, StatusCode, header},
     middleware::from_fn_with_state,
-    response::Json as ResponseJson,
+    response::{
+        Json as ResponseJson,
+        sse::{Event, KeepAlive, Sse},
+    },
     routing::{get, post},
 };
+use futures::stream::{self, Stream};
 use db::models::{
     execution_process::{ExecutionProcess, ExecutionProcessRunReason},
     execution_process_logs::ExecutionProcessLogs,
@@ -33,26 +38,844 @@ use executors::{
     profile::ExecutorProfileId,
 };
 use serde::{Deserialize, Serialize};
-use services::services::{container::ContainerService, worktree_manager::WorktreeManager};
-use std::path::{Path, PathBuf};
+use services::services::{
+    container::ContainerService,
+    ralph_notifier::{RalphNotifierService, RalphTransitionEvent, verify_hmac_sha256},
+    worktree_manager::WorktreeManager,
+};
+use std::{
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError, middleware::load_task_middleware};
 
+/// Default cap on `RalphBuild` iterations before a non-converging loop is
+/// marked `Failed` rather than left running forever.
+const DEFAULT_MAX_RALPH_ITERATIONS: u32 = 25;
+
+/// Default cap on automatic retries of a transiently-failed `RalphPlan`/
+/// `RalphBuild` process before giving up and transitioning to `Failed`.
+const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Base delay for the retry backoff (`base * 2^attempt`, capped).
+const RETRY_BACKOFF_BASE_MS: u64 = 2_000;
+const RETRY_BACKOFF_CAP_MS: u64 = 60_000;
+
+/// Name of the file an agent can write to `.ralph-vibe-kanban/` to signal
+/// convergence even if `IMPLEMENTATION_PLAN.md`'s checklist isn't fully
+/// checked off (e.g. the plan turned out to need no further work).
+const RALPH_DONE_SENTINEL: &str = "RALPH_DONE";
+
+/// Comma-separated pre-shared keys this deployment accepts inbound Ralph
+/// webhook signatures under, loaded once by `router()`. Rotate a key by
+/// adding the new value before removing the old one and redeploying.
+const RALPH_WEBHOOK_KEYS_ENV_VAR: &str = "VIBE_KANBAN_RALPH_WEBHOOK_KEYS";
+
+/// Process-wide Ralph notifier: one broadcast bus + webhook dispatch table
+/// shared by every task, so the SSE log stream and external integrations
+/// observe the exact same transition events.
+static RALPH_NOTIFIER: OnceLock<RalphNotifierService> = OnceLock::new();
+
+fn ralph_notifier() -> &'static RalphNotifierService {
+    RALPH_NOTIFIER.get_or_init(RalphNotifierService::new)
+}
+
+/// Pre-shared keys this deployment accepts signatures from on
+/// `POST .../ralph/webhook`. A signature is valid if it matches under any
+/// key in the set, so a key can be rotated by adding the new one before
+/// removing the old.
+static RALPH_WEBHOOK_KEYS: OnceLock<std::sync::RwLock<std::collections::HashSet<String>>> =
+    OnceLock::new();
+
+fn ralph_webhook_keys() -> &'static std::sync::RwLock<std::collections::HashSet<String>> {
+    RALPH_WEBHOOK_KEYS.get_or_init(|| std::sync::RwLock::new(std::collections::HashSet::new()))
+}
+
+/// Register a pre-shared key this deployment will accept inbound Ralph
+/// webhook signatures under. Called by `router()` for every key configured
+/// via `RALPH_WEBHOOK_KEYS_ENV_VAR`; also available for ops tooling that
+/// provisions/rotates a secret without a redeploy.
+pub fn add_ralph_webhook_key(key: String) {
+    ralph_webhook_keys().write().unwrap().insert(key);
+}
+
+/// Revoke a previously registered pre-shared webhook key.
+pub fn remove_ralph_webhook_key(key: &str) {
+    ralph_webhook_keys().write().unwrap().remove(key);
+}
+
+/// Load `RALPH_WEBHOOK_KEYS_ENV_VAR` into `ralph_webhook_keys()`, if set.
+/// Called once from `router()` so the deployment's configured keys are in
+/// place before any webhook request can arrive.
+fn load_ralph_webhook_keys_from_env() {
+    let Ok(raw) = std::env::var(RALPH_WEBHOOK_KEYS_ENV_VAR) else {
+        return;
+    };
+
+    for key in raw.split(',').map(str::trim).filter(|key| !key.is_empty()) {
+        add_ralph_webhook_key(key.to_string());
+    }
+}
+
+/// Most recent external event (commit SHA, PR number, etc.) that triggered
+/// a webhook-initiated run for a given task, keyed by task id. Read back by
+/// `get_execution_details` as `RalphExecutionDetailsResponse::triggered_by`.
+static RALPH_WEBHOOK_TRIGGERS: OnceLock<std::sync::Mutex<std::collections::HashMap<Uuid, String>>> =
+    OnceLock::new();
+
+fn ralph_webhook_triggers() -> &'static std::sync::Mutex<std::collections::HashMap<Uuid, String>> {
+    RALPH_WEBHOOK_TRIGGERS.get_or_init(|| std::sync::Mutex::new(std::collections::HashMap::new()))
+}
+
+/// Look up the task's project and emit a `RalphTransitionEvent` for an
+/// `old_status` -> `new_status` transition. Best-effort: a failure to
+/// resolve the project is logged, not propagated, since a notification
+/// should never block the status change it describes.
+async fn emit_ralph_transition(
+    pool: &sqlx::SqlitePool,
+    task: &Task,
+    old_status: RalphStatus,
+    new_status: RalphStatus,
+    exit_code: Option<i64>,
+    iteration: Option<u32>,
+) {
+    let project_id = match task.parent_project(pool).await {
+        Ok(Some(project)) => project.id,
+        Ok(None) => {
+            tracing::warn!("Ralph transition for task {} has no parent project; not notifying", task.id);
+            return;
+        }
+        Err(e) => {
+            tracing::warn!("Failed to resolve parent project for Ralph transition on task {}: {}", task.id, e);
+            return;
+        }
+    };
+
+    ralph_notifier()
+        .emit(RalphTransitionEvent {
+            task_id: task.id,
+            project_id,
+            old_status: format!("{:?}", old_status),
+            new_status: format!("{:?}", new_status),
+            exit_code,
+            iteration,
+            occurred_at: chrono::Utc::now().to_rfc3339(),
+        })
+        .await;
+}
+
+/// Default global cap on concurrently-running Ralph plan/build executions.
+/// Per-project overrides can be set tighter via `RalphScheduler::set_project_limit`.
+const DEFAULT_MAX_CONCURRENT_RALPH_RUNS: usize = 4;
+
+/// What a queued job does once it's granted a slot: re-run the launch a
+/// caller originally requested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RalphJobKind {
+    StartPlan,
+    Approve,
+    Replan,
+    Restart,
+}
+
+impl RalphJobKind {
+    /// `(run_reason, executor variant, target ralph_status, re-seed .ralph-vibe-kanban?)`
+    /// - mirrors exactly what each handler did inline before the scheduler
+    /// sat in front of it.
+    fn launch_params(self) -> (ExecutionProcessRunReason, &'static str, RalphStatus, bool) {
+        match self {
+            RalphJobKind::StartPlan => (ExecutionProcessRunReason::RalphPlan, "PLAN", RalphStatus::Planning, true),
+            RalphJobKind::Approve => (ExecutionProcessRunReason::RalphBuild, "BUILD", RalphStatus::Building, false),
+            RalphJobKind::Replan => (ExecutionProcessRunReason::RalphPlan, "PLAN", RalphStatus::Planning, false),
+            RalphJobKind::Restart => (ExecutionProcessRunReason::RalphPlan, "PLAN", RalphStatus::Planning, true),
+        }
+    }
+}
+
+struct RalphQueuedJob {
+    task_id: Uuid,
+    project_id: Uuid,
+    kind: RalphJobKind,
+}
+
+/// Result of `RalphScheduler::enqueue`.
+enum RalphEnqueueOutcome {
+    Started(ExecutionProcess),
+    Queued(usize),
+}
+
+#[derive(Default)]
+struct RalphSchedulerState {
+    queue: std::collections::VecDeque<RalphQueuedJob>,
+    /// task_id -> project_id, for every job currently occupying a slot
+    /// (i.e. its Ralph status hasn't reached a terminal state yet).
+    running_tasks: std::collections::HashMap<Uuid, Uuid>,
+    running_by_project: std::collections::HashMap<Uuid, usize>,
+    project_limits: std::collections::HashMap<Uuid, usize>,
+}
+
+/// Project-level concurrency gate in front of `start_plan`/`approve`/
+/// `replan`/`restart`: instead of launching inline, those handlers enqueue
+/// a job here, and it only gets promoted to an actual execution once its
+/// project (and the deployment overall) has a free slot. A job's slot is
+/// freed the moment its task reaches a terminal `RalphStatus`
+/// (`Completed`/`Failed`/`None`), observed via the same `RalphNotifierService`
+/// bus that drives webhook delivery - see `ensure_drain_task`.
+struct RalphScheduler {
+    state: std::sync::Mutex<RalphSchedulerState>,
+    global_limit: usize,
+}
+
+impl RalphScheduler {
+    fn new() -> Self {
+        Self {
+            state: std::sync::Mutex::new(RalphSchedulerState::default()),
+            global_limit: DEFAULT_MAX_CONCURRENT_RALPH_RUNS,
+        }
+    }
+
+    /// Override the concurrent-run limit for a single project; absent an
+    /// override, `global_limit` applies to each project individually too.
+    #[allow(dead_code)]
+    fn set_project_limit(&self, project_id: Uuid, limit: usize) {
+        self.state.lock().unwrap().project_limits.insert(project_id, limit);
+    }
+
+    fn project_limit(state: &RalphSchedulerState, global_limit: usize, project_id: Uuid) -> usize {
+        state.project_limits.get(&project_id).copied().unwrap_or(global_limit)
+    }
+
+    /// True if this task already has a job queued or occupying a slot -
+    /// callers use this to reject a second start/approve/replan/restart for
+    /// the same task rather than double-enqueueing it.
+    fn is_active(&self, task_id: Uuid) -> bool {
+        let state = self.state.lock().unwrap();
+        state.running_tasks.contains_key(&task_id) || state.queue.iter().any(|j| j.task_id == task_id)
+    }
+
+    /// 1-based position in the queue, or `None` if the task isn't queued
+    /// (it may be running, or have no job at all).
+    fn queue_position(&self, task_id: Uuid) -> Option<usize> {
+        let state = self.state.lock().unwrap();
+        state.queue.iter().position(|j| j.task_id == task_id).map(|i| i + 1)
+    }
+
+    /// Remove a task's queued job, if any, before it ever acquires a slot.
+    /// Returns whether a job was actually removed.
+    fn remove_from_queue(&self, task_id: Uuid) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let before = state.queue.len();
+        state.queue.retain(|j| j.task_id != task_id);
+        state.queue.len() != before
+    }
+
+    /// Enqueue a launch job. If a slot is free, launches it immediately
+    /// (awaiting the launch so a caller gets back the real `process_id`);
+    /// otherwise parks it in the queue and returns its position.
+    async fn enqueue(
+        &'static self,
+        deployment: &DeploymentImpl,
+        task: &Task,
+        workspace: &Workspace,
+        project_id: Uuid,
+        kind: RalphJobKind,
+    ) -> Result<RalphEnqueueOutcome, ApiError> {
+        ensure_drain_task(deployment);
+
+        let mut state = self.state.lock().unwrap();
+        let project_running = *state.running_by_project.get(&project_id).unwrap_or(&0);
+        let global_running = state.running_tasks.len();
+        let limit = Self::project_limit(&state, self.global_limit, project_id);
+
+        if global_running < self.global_limit && project_running < limit {
+            state.running_tasks.insert(task.id, project_id);
+            *state.running_by_project.entry(project_id).or_insert(0) += 1;
+            drop(state);
+
+            let (run_reason, variant, new_status, do_setup) = kind.launch_params();
+            return match launch_ralph_execution(deployment, task, workspace, run_reason, variant, new_status, do_setup)
+                .await
+            {
+                Ok(process) => Ok(RalphEnqueueOutcome::Started(process)),
+                Err(e) => {
+                    self.release(deployment, task.id);
+                    Err(e)
+                }
+            };
+        }
+
+        state.queue.push_back(RalphQueuedJob { task_id: task.id, project_id, kind });
+        Ok(RalphEnqueueOutcome::Queued(state.queue.len()))
+    }
+
+    /// Called when a running task's status reaches a terminal state: frees
+    /// its slot and promotes the first queued job whose project now has
+    /// room (at most one promotion per release, since exactly one slot
+    /// just freed).
+    fn release(&'static self, deployment: &DeploymentImpl, task_id: Uuid) {
+        let mut state = self.state.lock().unwrap();
+        let Some(project_id) = state.running_tasks.remove(&task_id) else {
+            return;
+        };
+        if let Some(count) = state.running_by_project.get_mut(&project_id) {
+            *count = count.saturating_sub(1);
+        }
+
+        if state.running_tasks.len() >= self.global_limit {
+            return;
+        }
+
+        let promote_idx = state.queue.iter().position(|job| {
+            let running = *state.running_by_project.get(&job.project_id).unwrap_or(&0);
+            running < Self::project_limit(&state, self.global_limit, job.project_id)
+        });
+
+        let Some(idx) = promote_idx else {
+            return;
+        };
+        let job = state.queue.remove(idx).expect("index came from this queue");
+        state.running_tasks.insert(job.task_id, job.project_id);
+        *state.running_by_project.entry(job.project_id).or_insert(0) += 1;
+        drop(state);
+
+        let deployment = deployment.clone();
+        tokio::spawn(async move {
+            run_ralph_job(deployment, job.task_id, job.kind).await;
+        });
+    }
+}
+
+static RALPH_SCHEDULER: OnceLock<RalphScheduler> = OnceLock::new();
+
+fn ralph_scheduler() -> &'static RalphScheduler {
+    RALPH_SCHEDULER.get_or_init(RalphScheduler::new)
+}
+
+/// Lazily spawn the single background task that watches the notifier bus
+/// for terminal transitions and frees/promotes scheduler slots accordingly.
+/// A plain function-local `static` (rather than a method on `RalphScheduler`)
+/// keeps the spawned future from needing a borrow of the scheduler itself.
+fn ensure_drain_task(deployment: &DeploymentImpl) {
+    static STARTED: OnceLock<()> = OnceLock::new();
+    if STARTED.set(()).is_err() {
+        return;
+    }
+
+    let deployment = deployment.clone();
+    tokio::spawn(async move {
+        let mut rx = ralph_notifier().subscribe();
+        while let Ok(event) = rx.recv().await {
+            if matches!(event.new_status.as_str(), "Completed" | "Failed" | "None") {
+                ralph_scheduler().release(&deployment, event.task_id);
+            }
+        }
+    });
+}
+
+/// Re-fetches the task/workspace (time has passed since the job was
+/// enqueued) and performs the actual launch. Shared by every job kind so
+/// the scheduler's promotion path and a handler's immediate path behave
+/// identically.
+async fn run_ralph_job(deployment: DeploymentImpl, task_id: Uuid, kind: RalphJobKind) {
+    let pool = &deployment.db().pool;
+
+    let task = match Task::find_by_id(pool, task_id).await {
+        Ok(Some(task)) => task,
+        Ok(None) => {
+            tracing::warn!("Ralph scheduler: task {} disappeared before its turn; skipping", task_id);
+            ralph_scheduler().release(&deployment, task_id);
+            return;
+        }
+        Err(e) => {
+            tracing::warn!("Ralph scheduler: failed to load task {}: {}", task_id, e);
+            ralph_scheduler().release(&deployment, task_id);
+            return;
+        }
+    };
+
+    let workspaces = Workspace::fetch_all(pool, Some(task_id)).await.unwrap_or_default();
+    let Some(workspace) = workspaces.first() else {
+        tracing::warn!("Ralph scheduler: no workspace for task {}; skipping", task_id);
+        ralph_scheduler().release(&deployment, task_id);
+        return;
+    };
+
+    let (run_reason, variant, new_status, do_setup) = kind.launch_params();
+
+    if let Err(e) = launch_ralph_execution(&deployment, &task, workspace, run_reason, variant, new_status, do_setup).await
+    {
+        tracing::warn!("Ralph scheduler: launch failed for task {}: {}", task_id, e);
+        // The launch never got as far as changing ralph_status, so there's
+        // no terminal transition coming from the notifier bus to free this
+        // slot - release it here instead.
+        ralph_scheduler().release(&deployment, task_id);
+    }
+}
+
+/// Shared by every job kind's launch: re-seeds `.ralph-vibe-kanban` when
+/// requested, flips `ralph_status`, starts the execution process, and kicks
+/// off its artifacts capture. Used both by a handler's immediate path (a
+/// slot was free) and by the scheduler's queued-promotion path.
+#[allow(clippy::too_many_arguments)]
+async fn launch_ralph_execution(
+    deployment: &DeploymentImpl,
+    task: &Task,
+    workspace: &Workspace,
+    run_reason: ExecutionProcessRunReason,
+    variant: &str,
+    new_status: RalphStatus,
+    do_setup: bool,
+) -> Result<ExecutionProcess, ApiError> {
+    let pool = &deployment.db().pool;
+
+    if do_setup {
+        setup_ralph_for_workspace(pool, workspace).await?;
+    }
+
+    Task::update_ralph_status(pool, task.id, new_status).await?;
+    emit_ralph_transition(pool, task, task.ralph_status, new_status, None, None).await;
+
+    let spec_content = task.description.as_ref().cloned().unwrap_or_default();
+    let session = Session::create(
+        pool,
+        &CreateSession {
+            executor: Some("RALPH".to_string()),
+        },
+        Uuid::new_v4(),
+        workspace.id,
+    )
+    .await?;
+
+    let executor_action = ExecutorAction::new(
+        ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+            prompt: spec_content,
+            executor_profile_id: ExecutorProfileId {
+                executor: BaseCodingAgent::Ralph,
+                variant: Some(variant.to_string()),
+            },
+            working_dir: None,
+        }),
+        None, // CRITICAL: next_action must be None for Ralph
+    );
+
+    let execution_process = deployment
+        .container()
+        .start_execution(workspace, &session, &executor_action, &run_reason)
+        .await?;
+
+    tracing::info!(
+        "Ralph scheduler started {:?} for task {} (workspace={}, process={})",
+        run_reason,
+        task.id,
+        workspace.id,
+        execution_process.id
+    );
+
+    if let Err(e) = capture_ralph_run_start(workspace, &execution_process, run_reason).await {
+        tracing::warn!("Failed to capture Ralph run-start artifacts for process {}: {}", execution_process.id, e);
+    }
+
+    Ok(execution_process)
+}
+
+/// Per-iteration bookkeeping persisted alongside the worktree so the loop
+/// driver can detect "no progress" and `GET /iterations` can report history.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct RalphLoopState {
+    iterations: Vec<RalphIterationRecord>,
+    /// Consecutive iterations with no checklist movement.
+    stalled_count: u32,
+    /// Consecutive transient failures retried so far for the current
+    /// process; reset to 0 as soon as a process succeeds.
+    retry_count: u32,
+    failed_attempts: Vec<RalphFailedAttempt>,
+    /// Set by `cancel()` so an in-flight loop driver or retry doesn't
+    /// respawn a new iteration after the user cancelled the task.
+    cancelled: bool,
+}
+
+/// A retried attempt, kept so `get_execution_details` can show full retry
+/// history instead of just the last process.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RalphFailedAttempt {
+    pub run_reason: String,
+    pub exit_code: Option<i64>,
+    pub attempted_at: String,
+    pub retryable: bool,
+}
+
+/// Whether a failed process is worth retrying automatically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FailureKind {
+    /// Killed, or a worktree/container setup error - likely to succeed on
+    /// a clean re-attempt.
+    Transient,
+    /// e.g. spec validation failure - retrying with the same input would
+    /// just fail the same way.
+    Logical,
+}
+
+/// `ExecutionProcessStatus` values treated as transient vs. logical. Killed
+/// processes (OOM, preempted runner, cancelled) are transient; a completed
+/// process that nonetheless exited non-zero is treated as a logical failure
+/// (the agent ran and decided the spec/task couldn't be satisfied).
+fn classify_failure(process_status: &str, exit_code: Option<i64>) -> FailureKind {
+    if process_status.eq_ignore_ascii_case("killed") || exit_code.is_none() {
+        FailureKind::Transient
+    } else {
+        FailureKind::Logical
+    }
+}
+
+/// `base * 2^attempt`, capped, plus up to 20% jitter.
+fn compute_backoff_delay(attempt: u32) -> std::time::Duration {
+    let exp = RETRY_BACKOFF_BASE_MS.saturating_mul(1u64 << attempt.min(20));
+    let capped = exp.min(RETRY_BACKOFF_CAP_MS);
+    let jitter = (capped as f64 * 0.2 * rand_fraction()) as u64;
+    std::time::Duration::from_millis(capped + jitter)
+}
+
+/// A small dependency-free source of jitter; doesn't need to be
+/// cryptographically random, just spread retries out a bit.
+fn rand_fraction() -> f64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    (nanos % 1000) as f64 / 1000.0
+}
+
+/// One iteration's outcome, as returned by `GET /tasks/:id/ralph/iterations`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RalphIterationRecord {
+    pub iteration: u32,
+    pub exit_code: Option<i64>,
+    pub checked_items: usize,
+    pub total_items: usize,
+    /// Checked-item count minus the previous iteration's, i.e. how much
+    /// progress this iteration made.
+    pub checkbox_delta: i64,
+}
+
+/// Response for `GET /tasks/:id/ralph/iterations`
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct RalphIterationsResponse {
+    pub iterations: Vec<RalphIterationRecord>,
+    pub max_iterations: u32,
+}
+
+fn ralph_loop_state_path(worktree_path: &Path) -> PathBuf {
+    Path::new(worktree_path)
+        .join(".ralph-vibe-kanban")
+        .join("loop_state.json")
+}
+
+async fn read_ralph_loop_state(worktree_path: &Path) -> RalphLoopState {
+    match tokio::fs::read_to_string(ralph_loop_state_path(worktree_path)).await {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+        Err(_) => RalphLoopState::default(),
+    }
+}
+
+async fn write_ralph_loop_state(worktree_path: &Path, state: &RalphLoopState) -> Result<(), ApiError> {
+    let path = ralph_loop_state_path(worktree_path);
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| ApiError::BadRequest(format!("Failed to persist Ralph loop state: {}", e)))?;
+    }
+    let content = serde_json::to_string_pretty(state)
+        .map_err(|e| ApiError::BadRequest(format!("Failed to serialize Ralph loop state: {}", e)))?;
+    tokio::fs::write(&path, content)
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Failed to persist Ralph loop state: {}", e)))?;
+    Ok(())
+}
+
+/// Filenames written into each run's artifacts directory (see
+/// `ralph_artifact_dir`).
+const ARTIFACT_PLAN_START: &str = "plan_start.md";
+const ARTIFACT_PLAN_END: &str = "plan_end.md";
+const ARTIFACT_OUTPUT_LOG: &str = "output.log";
+const ARTIFACT_DIFF: &str = "diff.patch";
+const ARTIFACT_MANIFEST: &str = "manifest.json";
+
+/// Root directory under which every Ralph execution process gets its own
+/// artifacts subdirectory, keyed by workspace and process id. Deliberately
+/// outside the worktree so a run's history survives the worktree being
+/// reset, reused by a later iteration, or removed entirely.
+fn ralph_artifacts_root() -> PathBuf {
+    let base = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share")))
+        .unwrap_or_else(std::env::temp_dir);
+    base.join("vibe-kanban").join("ralph-artifacts")
+}
+
+fn ralph_artifact_dir(workspace_id: Uuid, process_id: Uuid) -> PathBuf {
+    ralph_artifacts_root()
+        .join(workspace_id.to_string())
+        .join(process_id.to_string())
+}
+
+/// One downloadable file in a run's artifacts directory. `name` doubles as
+/// the path segment in `GET .../ralph/artifacts/:process_id/:name`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RalphArtifactSummary {
+    pub name: String,
+    pub content_type: String,
+    pub size_bytes: u64,
+    pub created_at: String,
+}
+
+/// Small JSON manifest persisted alongside a run's log/diff/plan snapshots,
+/// returned as-is by `GET /tasks/:id/ralph/artifacts/:process_id`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RalphRunManifest {
+    pub process_id: Uuid,
+    pub workspace_id: Uuid,
+    pub run_reason: String,
+    pub started_at: String,
+    pub completed_at: Option<String>,
+    pub exit_code: Option<i64>,
+    /// Downloadable files captured for this run so far. Populated by
+    /// re-scanning the run's artifacts directory each time the manifest is
+    /// written, rather than tracked incrementally, so it can never drift
+    /// from what's actually on disk. Defaulted for manifests written before
+    /// this field existed.
+    #[serde(default)]
+    pub artifacts: Vec<RalphArtifactSummary>,
+}
+
+/// Content-type for a known artifact filename; falls back to a generic
+/// binary type for anything unrecognized rather than guessing.
+fn artifact_content_type(name: &str) -> &'static str {
+    match name {
+        ARTIFACT_PLAN_START | ARTIFACT_PLAN_END => "text/markdown; charset=utf-8",
+        ARTIFACT_OUTPUT_LOG => "text/plain; charset=utf-8",
+        ARTIFACT_DIFF => "text/x-diff; charset=utf-8",
+        ARTIFACT_MANIFEST => "application/json",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Re-scan `dir` for the known artifact filenames and rebuild the manifest's
+/// `artifacts` list from whatever actually exists on disk right now.
+async fn refresh_artifact_summaries(dir: &Path, manifest: &mut RalphRunManifest) {
+    let mut artifacts = Vec::new();
+    for name in [ARTIFACT_PLAN_START, ARTIFACT_PLAN_END, ARTIFACT_OUTPUT_LOG, ARTIFACT_DIFF] {
+        let Ok(metadata) = tokio::fs::metadata(dir.join(name)).await else {
+            continue;
+        };
+        let created_at = metadata
+            .modified()
+            .map(chrono::DateTime::<chrono::Utc>::from)
+            .unwrap_or_else(|_| chrono::Utc::now())
+            .to_rfc3339();
+        artifacts.push(RalphArtifactSummary {
+            name: name.to_string(),
+            content_type: artifact_content_type(name).to_string(),
+            size_bytes: metadata.len(),
+            created_at,
+        });
+    }
+    manifest.artifacts = artifacts;
+}
+
+async fn write_ralph_artifact_manifest(dir: &Path, manifest: &RalphRunManifest) -> Result<(), ApiError> {
+    let content = serde_json::to_string_pretty(manifest)
+        .map_err(|e| ApiError::BadRequest(format!("Failed to serialize Ralph run manifest: {}", e)))?;
+    tokio::fs::write(dir.join(ARTIFACT_MANIFEST), content)
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Failed to persist Ralph run manifest: {}", e)))
+}
+
+async fn read_ralph_artifact_manifest(dir: &Path) -> Option<RalphRunManifest> {
+    let content = tokio::fs::read_to_string(dir.join(ARTIFACT_MANIFEST)).await.ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Create `<data_dir>/ralph-artifacts/<workspace_id>/<process_id>/` and
+/// snapshot `IMPLEMENTATION_PLAN.md` as it looked when this run started.
+/// Best-effort: a failure here shouldn't block the run itself, so callers
+/// log and continue rather than propagate.
+async fn capture_ralph_run_start(
+    workspace: &Workspace,
+    process: &ExecutionProcess,
+    run_reason: ExecutionProcessRunReason,
+) -> Result<(), ApiError> {
+    let dir = ralph_artifact_dir(workspace.id, process.id);
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Failed to create Ralph artifacts directory: {}", e)))?;
+
+    if let Some(worktree_path) = workspace.container_ref.as_ref() {
+        let plan_path = Path::new(worktree_path).join("IMPLEMENTATION_PLAN.md");
+        if let Ok(content) = tokio::fs::read_to_string(&plan_path).await {
+            let _ = tokio::fs::write(dir.join(ARTIFACT_PLAN_START), content).await;
+        }
+    }
+
+    let mut manifest = RalphRunManifest {
+        process_id: process.id,
+        workspace_id: workspace.id,
+        run_reason: format!("{:?}", run_reason),
+        started_at: chrono::Utc::now().to_rfc3339(),
+        completed_at: None,
+        exit_code: None,
+        artifacts: Vec::new(),
+    };
+    refresh_artifact_summaries(&dir, &mut manifest).await;
+    write_ralph_artifact_manifest(&dir, &manifest).await
+}
+
+/// Fill in a run's artifacts once its process has exited: the plan as it
+/// looked at the end, the full combined log, the git diff the run produced
+/// in the worktree, and the manifest's exit code + timing. Called for both
+/// successful and failed completions so post-mortem analysis doesn't depend
+/// on a worktree that may already have moved on to the next iteration.
+pub async fn capture_ralph_run_completion(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    process: &ExecutionProcess,
+) -> Result<(), ApiError> {
+    let dir = ralph_artifact_dir(workspace.id, process.id);
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Failed to create Ralph artifacts directory: {}", e)))?;
+    let pool = &deployment.db().pool;
+
+    if let Some(worktree_path) = workspace.container_ref.as_ref() {
+        let worktree_path = Path::new(worktree_path);
+
+        if let Ok(content) = tokio::fs::read_to_string(worktree_path.join("IMPLEMENTATION_PLAN.md")).await {
+            let _ = tokio::fs::write(dir.join(ARTIFACT_PLAN_END), content).await;
+        }
+
+        if let Ok(output) = tokio::process::Command::new("git")
+            .args(["diff", "HEAD"])
+            .current_dir(worktree_path)
+            .output()
+            .await
+        {
+            let _ = tokio::fs::write(dir.join(ARTIFACT_DIFF), output.stdout).await;
+        }
+    }
+
+    if let Ok(log_records) = ExecutionProcessLogs::find_by_execution_id(pool, process.id).await {
+        let combined: String = log_records
+            .iter()
+            .flat_map(|r| r.logs.lines())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let _ = tokio::fs::write(dir.join(ARTIFACT_OUTPUT_LOG), combined).await;
+    }
+
+    let mut manifest = read_ralph_artifact_manifest(&dir).await.unwrap_or(RalphRunManifest {
+        process_id: process.id,
+        workspace_id: workspace.id,
+        run_reason: format!("{:?}", process.run_reason),
+        started_at: chrono::Utc::now().to_rfc3339(),
+        completed_at: None,
+        exit_code: None,
+        artifacts: Vec::new(),
+    });
+    manifest.exit_code = process.exit_code;
+    manifest.completed_at = Some(
+        process
+            .completed_at
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_else(|| chrono::Utc::now().to_rfc3339()),
+    );
+    refresh_artifact_summaries(&dir, &mut manifest).await;
+
+    write_ralph_artifact_manifest(&dir, &manifest).await
+}
+
+/// Count `- [ ]`/`- [x]` Markdown checklist items in an implementation plan.
+/// Returns `(checked, total)`.
+fn count_checklist_progress(plan_content: &str) -> (usize, usize) {
+    let mut checked = 0;
+    let mut total = 0;
+    for line in plan_content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("- [x]") || trimmed.starts_with("- [X]") {
+            checked += 1;
+            total += 1;
+        } else if trimmed.starts_with("- [ ]") {
+            total += 1;
+        }
+    }
+    (checked, total)
+}
+
+/// Decide what to do after a `RalphBuild` process exits: keep looping
+/// (spawn another build), or converge to a terminal state. Kept as a pure
+/// function so the loop logic is easy to reason about independent of I/O.
+enum RalphLoopDecision {
+    ContinueBuilding,
+    Completed,
+    Failed { reason: String },
+}
+
+/// Called only once a process has already exited 0; a non-zero exit is
+/// handled upstream by `handle_ralph_process_failure`'s retry gate.
+fn decide_ralph_loop_action(
+    checked: usize,
+    total: usize,
+    sentinel_present: bool,
+    iteration: u32,
+    max_iterations: u32,
+    stalled_count: u32,
+) -> RalphLoopDecision {
+    if sentinel_present || (total > 0 && checked == total) {
+        return RalphLoopDecision::Completed;
+    }
+
+    if iteration >= max_iterations {
+        return RalphLoopDecision::Failed {
+            reason: format!(
+                "Ralph build hit the {}-iteration cap without converging",
+                max_iterations
+            ),
+        };
+    }
+
+    // stalled_count is incremented by the caller *before* this check, so a
+    // value of >= 2 means this is the second iteration in a row with no
+    // checklist movement - bail instead of burning further tokens.
+    if stalled_count >= 2 {
+        return RalphLoopDecision::Failed {
+            reason: "Ralph build made no progress for two consecutive iterations".to_string(),
+        };
+    }
+
+    RalphLoopDecision::ContinueBuilding
+}
+
 /// Response for Ralph status (for debugging/smoke testing)
 #[derive(Debug, Serialize, Deserialize, TS)]
 pub struct RalphStatusResponse {
     pub ralph_status: RalphStatus,
     pub task_id: Uuid,
+    /// 1-based position in the scheduler's queue; `None` if the task has no
+    /// pending job (either nothing queued, or it already has a slot).
+    pub queue_position: Option<usize>,
 }
 
-/// Response when starting a Ralph operation
+/// Response when starting a Ralph operation. `process_id` is `None` when
+/// the run was queued rather than started immediately - see
+/// `queue_position` for where it landed.
 #[derive(Debug, Serialize, Deserialize, TS)]
 pub struct RalphStartResponse {
     pub workspace_id: Uuid,
-    pub process_id: Uuid,
+    pub process_id: Option<Uuid>,
+    pub queue_position: Option<usize>,
 }
 
 /// Response for plan content
@@ -74,8 +897,34 @@ pub struct RalphExecutionDetailsResponse {
     pub run_reason: Option<String>,
     /// When the process completed (if finished)
     pub completed_at: Option<String>,
-    /// Last log content (stderr/stdout, truncated to last 50 lines)
+    /// Last log content, truncated to the last `?tail=` lines (default 50,
+    /// at most `MAX_LOG_TAIL`) of the combined log, then filtered to the
+    /// requested `?channel=` if one was given, combined (stdout + stderr +
+    /// system) otherwise. Kept for backward compatibility alongside the
+    /// per-channel tails below.
     pub last_logs: Option<String>,
+    /// Last `?tail=` (default 50) stdout lines, independent of the
+    /// `?channel=` filter.
+    pub last_stdout: Option<String>,
+    /// Last `?tail=` (default 50) stderr lines, independent of the
+    /// `?channel=` filter.
+    pub last_stderr: Option<String>,
+    /// Every failed build attempt recorded for the workspace (exit codes +
+    /// timestamps, and whether it was retryable), most recent last. Not
+    /// cleared when a later process succeeds.
+    pub retry_history: Vec<RalphFailedAttempt>,
+    /// 1-based position in the scheduler's queue; `None` if the task has no
+    /// pending job.
+    pub queue_position: Option<usize>,
+    /// External event (commit SHA, PR number, etc.) that triggered the
+    /// latest run via `POST .../ralph/webhook`, if any. `run_reason` itself
+    /// only distinguishes RalphPlan/RalphBuild, so this is where a
+    /// webhook-initiated run's provenance actually surfaces.
+    pub triggered_by: Option<String>,
+    /// Downloadable files captured for the latest run (plan snapshots,
+    /// diff, combined log), so the UI can list them next to the logs and
+    /// exit code. Empty if the run has no workspace/process yet.
+    pub artifacts: Vec<RalphArtifactSummary>,
 }
 
 /// Helper function to setup Ralph in a worktree
@@ -121,6 +970,121 @@ async fn setup_ralph_for_workspace(
     Ok(())
 }
 
+/// Handle a non-zero-exit `RalphPlan`/`RalphBuild` process: retry with
+/// exponential backoff if the failure looks transient and retries remain,
+/// otherwise transition the task to `Failed`. Re-runs
+/// `setup_ralph_for_workspace` before retrying, since a failed run may have
+/// left `.ralph-vibe-kanban` corrupted.
+async fn handle_ralph_process_failure(
+    deployment: &DeploymentImpl,
+    task: &Task,
+    workspace: &Workspace,
+    failed_process: &ExecutionProcess,
+    run_reason: ExecutionProcessRunReason,
+    state: &mut RalphLoopState,
+    worktree_path: &Path,
+) -> Result<(), ApiError> {
+    let pool = &deployment.db().pool;
+    let kind = classify_failure(&format!("{:?}", failed_process.status), failed_process.exit_code);
+
+    state.failed_attempts.push(RalphFailedAttempt {
+        run_reason: format!("{:?}", run_reason),
+        exit_code: failed_process.exit_code,
+        attempted_at: chrono::Utc::now().to_rfc3339(),
+        retryable: kind == FailureKind::Transient,
+    });
+
+    if state.cancelled {
+        write_ralph_loop_state(worktree_path, state).await?;
+        tracing::info!("Ralph {:?} for task {} was cancelled; not retrying", run_reason, task.id);
+        return Ok(());
+    }
+
+    if kind == FailureKind::Transient && state.retry_count < DEFAULT_MAX_RETRIES {
+        state.retry_count += 1;
+        let attempt = state.retry_count;
+        write_ralph_loop_state(worktree_path, state).await?;
+
+        let delay = compute_backoff_delay(attempt);
+        tracing::warn!(
+            "Ralph {:?} for task {} failed transiently (exit_code={:?}); retrying in {:?} (attempt {}/{})",
+            run_reason,
+            task.id,
+            failed_process.exit_code,
+            delay,
+            attempt,
+            DEFAULT_MAX_RETRIES
+        );
+        tokio::time::sleep(delay).await;
+
+        // A failed run may have corrupted .ralph-vibe-kanban; re-seed it
+        // before re-launching, same as restart() does from Failed.
+        setup_ralph_for_workspace(pool, workspace).await?;
+
+        let spec_content = task.description.as_ref().cloned().unwrap_or_default();
+        let variant = match run_reason {
+            ExecutionProcessRunReason::RalphPlan => "PLAN",
+            _ => "BUILD",
+        };
+
+        let session = Session::create(
+            pool,
+            &CreateSession {
+                executor: Some("RALPH".to_string()),
+            },
+            Uuid::new_v4(),
+            workspace.id,
+        )
+        .await?;
+
+        let executor_action = ExecutorAction::new(
+            ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                prompt: spec_content,
+                executor_profile_id: ExecutorProfileId {
+                    executor: BaseCodingAgent::Ralph,
+                    variant: Some(variant.to_string()),
+                },
+                working_dir: None,
+            }),
+            None, // CRITICAL: next_action must be None for Ralph
+        );
+
+        // Re-launch without leaving Planning/Building - the status transition
+        // already made when the original attempt started still applies.
+        let retried_process = deployment
+            .container()
+            .start_execution(workspace, &session, &executor_action, &run_reason)
+            .await?;
+
+        if let Err(e) = capture_ralph_run_start(workspace, &retried_process, run_reason).await {
+            tracing::warn!("Failed to capture Ralph run-start artifacts for process {}: {}", retried_process.id, e);
+        }
+
+        return Ok(());
+    }
+
+    write_ralph_loop_state(worktree_path, state).await?;
+    Task::update_ralph_status(pool, task.id, RalphStatus::Failed).await?;
+    tracing::warn!(
+        "Ralph {:?} failed for task {} (exit_code={:?}, retries_exhausted_or_logical={:?})",
+        run_reason,
+        task.id,
+        failed_process.exit_code,
+        kind
+    );
+    emit_ralph_transition(
+        pool,
+        task,
+        task.ralph_status,
+        RalphStatus::Failed,
+        failed_process.exit_code,
+        Some(state.iterations.len() as u32),
+    )
+    .await;
+
+    Ok(())
+}
+
 /// Helper function to create a workspace for Ralph if one doesn't exist
 /// This mirrors the logic from create_task_attempt but uses project repos
 async fn get_or_create_workspace_for_ralph(
@@ -230,6 +1194,7 @@ pub async fn get_status(
     Ok(ResponseJson(ApiResponse::success(RalphStatusResponse {
         ralph_status: task.ralph_status,
         task_id: task.id,
+        queue_position: ralph_scheduler().queue_position(task.id),
     })))
 }
 
@@ -242,6 +1207,16 @@ pub async fn start_plan(
     Extension(task): Extension<Task>,
     State(deployment): State<DeploymentImpl>,
 ) -> Result<(StatusCode, ResponseJson<ApiResponse<RalphStartResponse>>), ApiError> {
+    let response = do_start_plan(&deployment, &task).await?;
+    Ok((StatusCode::OK, ResponseJson(ApiResponse::success(response))))
+}
+
+/// Shared `start-plan` logic used by both the authenticated HTTP route and
+/// the HMAC-verified inbound webhook, so the two entry points can't drift.
+async fn do_start_plan(
+    deployment: &DeploymentImpl,
+    task: &Task,
+) -> Result<RalphStartResponse, ApiError> {
     let pool = &deployment.db().pool;
 
     // Validate state transition
@@ -253,73 +1228,149 @@ pub async fn start_plan(
     }
 
     // Verify task has a description (spec)
-    let spec_content = task
-        .description
+    task.description
         .as_ref()
         .filter(|d| !d.trim().is_empty())
         .ok_or_else(|| {
             ApiError::BadRequest("Task must have a description (spec) to use Ralph".to_string())
         })?;
 
+    if ralph_scheduler().is_active(task.id) {
+        return Err(ApiError::BadRequest(
+            "Ralph already has a queued or running job for this task".to_string(),
+        ));
+    }
+
     // Get or create a workspace for this task
-    let workspace = get_or_create_workspace_for_ralph(&deployment, &task).await?;
+    let workspace = get_or_create_workspace_for_ralph(deployment, task).await?;
 
-    // Setup Ralph in worktree - copy .ralph to .ralph-vibe-kanban with VK-specific prompts
-    setup_ralph_for_workspace(pool, &workspace).await?;
+    let project = task
+        .parent_project(pool)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Task has no parent project".to_string()))?;
 
-    // Update ralph_status to Planning BEFORE starting execution
-    Task::update_ralph_status(pool, task.id, RalphStatus::Planning).await?;
+    let outcome = ralph_scheduler()
+        .enqueue(deployment, task, &workspace, project.id, RalphJobKind::StartPlan)
+        .await?;
 
-    // Create a new session for this Ralph execution
-    let session = Session::create(
-        pool,
-        &CreateSession {
-            executor: Some("RALPH".to_string()),
+    Ok(match outcome {
+        RalphEnqueueOutcome::Started(process) => RalphStartResponse {
+            workspace_id: workspace.id,
+            process_id: Some(process.id),
+            queue_position: None,
         },
-        Uuid::new_v4(),
-        workspace.id,
-    )
-    .await?;
+        RalphEnqueueOutcome::Queued(position) => RalphStartResponse {
+            workspace_id: workspace.id,
+            process_id: None,
+            queue_position: Some(position),
+        },
+    })
+}
 
-    // Build executor action with Ralph executor
-    // Note: next_action must be None - Ralph handles its own completion
-    let executor_action = ExecutorAction::new(
-        ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
-            prompt: spec_content.clone(),
-            executor_profile_id: ExecutorProfileId {
-                executor: BaseCodingAgent::Ralph,
-                variant: Some("PLAN".to_string()),
-            },
-            working_dir: None,
-        }),
-        None, // CRITICAL: next_action must be None for Ralph
-    );
+/// Body of an inbound `POST .../ralph/webhook` request: a minimal,
+/// provider-agnostic envelope rather than e.g. the full GitHub push/PR
+/// payload shape, since the only thing Ralph needs out of it is what to
+/// record as the run's trigger and, for the deployment-level route, which
+/// task to start.
+#[derive(Debug, Deserialize)]
+pub struct RalphWebhookPayload {
+    /// External event identifier (commit SHA, PR number, etc.) recorded
+    /// against the task and surfaced via
+    /// `RalphExecutionDetailsResponse::triggered_by`.
+    pub external_ref: String,
+    /// Which task to start. Ignored by the task-scoped route (the task
+    /// comes from the path); required by the deployment-level route, which
+    /// has no task in its path.
+    #[serde(default)]
+    pub task_id: Option<Uuid>,
+}
 
-    // Start execution with RalphPlan run_reason
-    let execution_process = deployment
-        .container()
-        .start_execution(
-            &workspace,
-            &session,
-            &executor_action,
-            &ExecutionProcessRunReason::RalphPlan,
-        )
-        .await?;
+/// Verify `X-Hub-Signature-256: sha256=<hex>` against every pre-shared key
+/// registered for this deployment, accepting if any one matches. Returns a
+/// typed `ApiError` (rather than a bare bool) so callers can `?` straight
+/// through to a rejection response.
+fn verify_ralph_webhook_signature(headers: &HeaderMap, body: &[u8]) -> Result<(), ApiError> {
+    let header_value = headers
+        .get("X-Hub-Signature-256")
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| {
+            ApiError::BadRequest("Missing X-Hub-Signature-256 header".to_string())
+        })?;
+    let hex_signature = header_value.strip_prefix("sha256=").unwrap_or(header_value);
 
-    tracing::info!(
-        "Started Ralph plan for task {} (workspace={}, process={})",
-        task.id,
-        workspace.id,
-        execution_process.id
-    );
+    let keys = ralph_webhook_keys().read().unwrap();
+    if keys.is_empty() {
+        return Err(ApiError::BadRequest(
+            "No Ralph webhook keys configured for this deployment".to_string(),
+        ));
+    }
+    let verified = keys
+        .iter()
+        .any(|key| verify_hmac_sha256(key.as_bytes(), body, hex_signature));
 
-    Ok((
-        StatusCode::OK,
-        ResponseJson(ApiResponse::success(RalphStartResponse {
-            workspace_id: workspace.id,
-            process_id: execution_process.id,
-        })),
-    ))
+    if verified {
+        Ok(())
+    } else {
+        Err(ApiError::BadRequest(
+            "Ralph webhook signature verification failed".to_string(),
+        ))
+    }
+}
+
+/// Start Ralph plan mode for a task from an authenticated external event
+/// (e.g. a repo push or PR webhook), scoped to a known task.
+///
+/// POST /tasks/:id/ralph/webhook
+/// Valid from: None, Failed (same as `start_plan`)
+pub async fn webhook_start_plan(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, ResponseJson<ApiResponse<RalphStartResponse>>), ApiError> {
+    verify_ralph_webhook_signature(&headers, &body)?;
+    let payload: RalphWebhookPayload = serde_json::from_slice(&body)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid webhook payload: {}", e)))?;
+
+    ralph_webhook_triggers()
+        .lock()
+        .unwrap()
+        .insert(task.id, payload.external_ref);
+
+    let response = do_start_plan(&deployment, &task).await?;
+    Ok((StatusCode::OK, ResponseJson(ApiResponse::success(response))))
+}
+
+/// Deployment-level variant of `webhook_start_plan` for integrations that
+/// can't address a task by path (e.g. a single repo-wide webhook endpoint
+/// configured once with the VCS provider): the task is resolved from
+/// `RalphWebhookPayload::task_id` instead of the URL.
+///
+/// POST /ralph/webhook
+pub async fn webhook_start_plan_for_deployment(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, ResponseJson<ApiResponse<RalphStartResponse>>), ApiError> {
+    verify_ralph_webhook_signature(&headers, &body)?;
+    let payload: RalphWebhookPayload = serde_json::from_slice(&body)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid webhook payload: {}", e)))?;
+    let task_id = payload
+        .task_id
+        .ok_or_else(|| ApiError::BadRequest("Payload must include task_id".to_string()))?;
+
+    let pool = &deployment.db().pool;
+    let task = Task::find_by_id(pool, task_id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Task not found".to_string()))?;
+
+    ralph_webhook_triggers()
+        .lock()
+        .unwrap()
+        .insert(task.id, payload.external_ref);
+
+    let response = do_start_plan(&deployment, &task).await?;
+    Ok((StatusCode::OK, ResponseJson(ApiResponse::success(response))))
 }
 
 /// Get the implementation plan content
@@ -383,12 +1434,11 @@ pub async fn approve(
         )));
     }
 
-    // Get the spec content (validated when plan started)
-    let spec_content = task
-        .description
-        .as_ref()
-        .cloned()
-        .unwrap_or_default();
+    if ralph_scheduler().is_active(task.id) {
+        return Err(ApiError::BadRequest(
+            "Ralph already has a queued or running job for this task".to_string(),
+        ));
+    }
 
     // Get the workspace
     let workspaces = Workspace::fetch_all(pool, Some(task.id)).await?;
@@ -396,58 +1446,244 @@ pub async fn approve(
         ApiError::BadRequest("No workspace found for task".to_string())
     })?;
 
-    // Update ralph_status to Building BEFORE starting execution
-    Task::update_ralph_status(pool, task.id, RalphStatus::Building).await?;
+    let project = task
+        .parent_project(pool)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Task has no parent project".to_string()))?;
 
-    // Create a new session for build execution
-    let session = Session::create(
-        pool,
-        &CreateSession {
-            executor: Some("RALPH".to_string()),
+    let outcome = ralph_scheduler()
+        .enqueue(&deployment, &task, workspace, project.id, RalphJobKind::Approve)
+        .await?;
+
+    let response = match outcome {
+        RalphEnqueueOutcome::Started(process) => RalphStartResponse {
+            workspace_id: workspace.id,
+            process_id: Some(process.id),
+            queue_position: None,
         },
-        Uuid::new_v4(),
-        workspace.id,
+        RalphEnqueueOutcome::Queued(position) => RalphStartResponse {
+            workspace_id: workspace.id,
+            process_id: None,
+            queue_position: Some(position),
+        },
+    };
+
+    Ok((StatusCode::OK, ResponseJson(ApiResponse::success(response))))
+}
+
+/// Called once a `RalphBuild` execution process exits. Re-reads
+/// `IMPLEMENTATION_PLAN.md` from the worktree, decides whether the loop has
+/// converged, failed, or needs another `RalphBuild` iteration, and drives
+/// the `Task.ralph_status` transition accordingly. This is what makes
+/// `Building` self-terminating instead of requiring manual inspection after
+/// `approve()`'s single execution.
+///
+/// Wired into the container's execution-process-completed hook for
+/// processes with `run_reason == RalphBuild`.
+pub async fn handle_ralph_build_completion(
+    deployment: &DeploymentImpl,
+    task: &Task,
+    workspace: &Workspace,
+    completed_process: &ExecutionProcess,
+) -> Result<(), ApiError> {
+    let pool = &deployment.db().pool;
+
+    let worktree_path = workspace.container_ref.as_ref().ok_or_else(|| {
+        ApiError::BadRequest("Workspace has no container reference (worktree not set up)".to_string())
+    })?;
+    let worktree_path = Path::new(worktree_path);
+
+    let plan_path = worktree_path.join("IMPLEMENTATION_PLAN.md");
+    let plan_content = tokio::fs::read_to_string(&plan_path).await.unwrap_or_default();
+    let (checked, total) = count_checklist_progress(&plan_content);
+
+    let sentinel_present = tokio::fs::try_exists(
+        worktree_path.join(".ralph-vibe-kanban").join(RALPH_DONE_SENTINEL),
     )
-    .await?;
+    .await
+    .unwrap_or(false);
 
-    // Build executor action with Ralph executor in build mode
-    let executor_action = ExecutorAction::new(
-        ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
-            prompt: spec_content,
-            executor_profile_id: ExecutorProfileId {
-                executor: BaseCodingAgent::Ralph,
-                variant: Some("BUILD".to_string()),
-            },
-            working_dir: None,
-        }),
-        None, // CRITICAL: next_action must be None for Ralph
-    );
+    let mut state = read_ralph_loop_state(worktree_path).await;
 
-    // Start execution with RalphBuild run_reason
-    let execution_process = deployment
-        .container()
-        .start_execution(
+    if let Err(e) = capture_ralph_run_completion(deployment, workspace, completed_process).await {
+        tracing::warn!(
+            "Failed to capture Ralph run-completion artifacts for process {}: {}",
+            completed_process.id,
+            e
+        );
+    }
+
+    // A failed process is handed entirely to the retry gate, which either
+    // retries it or marks the task `Failed`; it never reaches the
+    // iteration-decision logic below, which only reasons about successful
+    // exits.
+    if completed_process.exit_code != Some(0) {
+        return handle_ralph_process_failure(
+            deployment,
+            task,
             workspace,
-            &session,
-            &executor_action,
-            &ExecutionProcessRunReason::RalphBuild,
+            completed_process,
+            ExecutionProcessRunReason::RalphBuild,
+            &mut state,
+            worktree_path,
         )
-        .await?;
+        .await;
+    }
+    state.retry_count = 0;
 
-    tracing::info!(
-        "Started Ralph build for task {} (workspace={}, process={})",
-        task.id,
-        workspace.id,
-        execution_process.id
+    let prev_progress = state.iterations.last().map(|r| (r.checked_items, r.total_items));
+    if prev_progress == Some((checked, total)) {
+        state.stalled_count += 1;
+    } else {
+        state.stalled_count = 0;
+    }
+
+    let iteration = state.iterations.len() as u32 + 1;
+    let checkbox_delta = checked as i64 - prev_progress.map(|(c, _)| c as i64).unwrap_or(0);
+
+    state.iterations.push(RalphIterationRecord {
+        iteration,
+        exit_code: completed_process.exit_code,
+        checked_items: checked,
+        total_items: total,
+        checkbox_delta,
+    });
+    write_ralph_loop_state(worktree_path, &state).await?;
+
+    let decision = decide_ralph_loop_action(
+        checked,
+        total,
+        sentinel_present,
+        iteration,
+        DEFAULT_MAX_RALPH_ITERATIONS,
+        state.stalled_count,
     );
 
-    Ok((
-        StatusCode::OK,
-        ResponseJson(ApiResponse::success(RalphStartResponse {
-            workspace_id: workspace.id,
-            process_id: execution_process.id,
-        })),
-    ))
+    match decision {
+        RalphLoopDecision::Completed => {
+            Task::update_ralph_status(pool, task.id, RalphStatus::Completed).await?;
+            tracing::info!("Ralph build converged for task {} after {} iteration(s)", task.id, iteration);
+            emit_ralph_transition(
+                pool,
+                task,
+                task.ralph_status,
+                RalphStatus::Completed,
+                completed_process.exit_code,
+                Some(iteration),
+            )
+            .await;
+        }
+        RalphLoopDecision::Failed { reason } => {
+            Task::update_ralph_status(pool, task.id, RalphStatus::Failed).await?;
+            tracing::warn!("Ralph build failed for task {}: {}", task.id, reason);
+            emit_ralph_transition(
+                pool,
+                task,
+                task.ralph_status,
+                RalphStatus::Failed,
+                completed_process.exit_code,
+                Some(iteration),
+            )
+            .await;
+        }
+        RalphLoopDecision::ContinueBuilding if state.cancelled => {
+            tracing::info!("Ralph build for task {} was cancelled; not spawning another iteration", task.id);
+        }
+        RalphLoopDecision::ContinueBuilding => {
+            emit_ralph_transition(
+                pool,
+                task,
+                task.ralph_status,
+                RalphStatus::Building,
+                completed_process.exit_code,
+                Some(iteration),
+            )
+            .await;
+
+            let spec_content = task.description.as_ref().cloned().unwrap_or_default();
+
+            let session = Session::create(
+                pool,
+                &CreateSession {
+                    executor: Some("RALPH".to_string()),
+                },
+                Uuid::new_v4(),
+                workspace.id,
+            )
+            .await?;
+
+            let executor_action = ExecutorAction::new(
+                ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                    prompt: spec_content,
+                    executor_profile_id: ExecutorProfileId {
+                        executor: BaseCodingAgent::Ralph,
+                        variant: Some("BUILD".to_string()),
+                    },
+                    working_dir: None,
+                }),
+                None, // CRITICAL: next_action must be None for Ralph
+            );
+
+            let execution_process = deployment
+                .container()
+                .start_execution(
+                    workspace,
+                    &session,
+                    &executor_action,
+                    &ExecutionProcessRunReason::RalphBuild,
+                )
+                .await?;
+
+            tracing::info!(
+                "Re-invoked Ralph build for task {} (iteration={}, process={})",
+                task.id,
+                iteration + 1,
+                execution_process.id
+            );
+
+            if let Err(e) = capture_ralph_run_start(
+                workspace,
+                &execution_process,
+                ExecutionProcessRunReason::RalphBuild,
+            )
+            .await
+            {
+                tracing::warn!(
+                    "Failed to capture Ralph run-start artifacts for process {}: {}",
+                    execution_process.id,
+                    e
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Get per-iteration Ralph build history for a task
+///
+/// GET /tasks/:id/ralph/iterations
+pub async fn get_iterations(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<RalphIterationsResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspaces = Workspace::fetch_all(pool, Some(task.id)).await?;
+    let workspace = workspaces
+        .first()
+        .ok_or_else(|| ApiError::BadRequest("No workspace found for task".to_string()))?;
+
+    let worktree_path = workspace.container_ref.as_ref().ok_or_else(|| {
+        ApiError::BadRequest("Workspace has no container reference".to_string())
+    })?;
+
+    let state = read_ralph_loop_state(Path::new(worktree_path)).await;
+
+    Ok(ResponseJson(ApiResponse::success(RalphIterationsResponse {
+        iterations: state.iterations,
+        max_iterations: DEFAULT_MAX_RALPH_ITERATIONS,
+    })))
 }
 
 /// Re-run plan mode
@@ -469,74 +1705,49 @@ pub async fn replan(
         )));
     }
 
-    // Get the spec content
-    let spec_content = task
-        .description
+    // Verify task has a description (spec)
+    task.description
         .as_ref()
         .filter(|d| !d.trim().is_empty())
-        .cloned()
         .ok_or_else(|| {
             ApiError::BadRequest("Task must have a description (spec) to use Ralph".to_string())
         })?;
 
+    if ralph_scheduler().is_active(task.id) {
+        return Err(ApiError::BadRequest(
+            "Ralph already has a queued or running job for this task".to_string(),
+        ));
+    }
+
     // Get the workspace
     let workspaces = Workspace::fetch_all(pool, Some(task.id)).await?;
     let workspace = workspaces.first().ok_or_else(|| {
         ApiError::BadRequest("No workspace found for task".to_string())
-    })?;
-
-    // Update ralph_status to Planning
-    Task::update_ralph_status(pool, task.id, RalphStatus::Planning).await?;
-
-    // Create a new session for re-planning
-    let session = Session::create(
-        pool,
-        &CreateSession {
-            executor: Some("RALPH".to_string()),
-        },
-        Uuid::new_v4(),
-        workspace.id,
-    )
-    .await?;
+    })?;
 
-    // Build executor action
-    let executor_action = ExecutorAction::new(
-        ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
-            prompt: spec_content,
-            executor_profile_id: ExecutorProfileId {
-                executor: BaseCodingAgent::Ralph,
-                variant: Some("PLAN".to_string()),
-            },
-            working_dir: None,
-        }),
-        None,
-    );
+    let project = task
+        .parent_project(pool)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Task has no parent project".to_string()))?;
 
-    // Start execution with RalphPlan run_reason
-    let execution_process = deployment
-        .container()
-        .start_execution(
-            workspace,
-            &session,
-            &executor_action,
-            &ExecutionProcessRunReason::RalphPlan,
-        )
+    let outcome = ralph_scheduler()
+        .enqueue(&deployment, &task, workspace, project.id, RalphJobKind::Replan)
         .await?;
 
-    tracing::info!(
-        "Started Ralph re-plan for task {} (workspace={}, process={})",
-        task.id,
-        workspace.id,
-        execution_process.id
-    );
-
-    Ok((
-        StatusCode::OK,
-        ResponseJson(ApiResponse::success(RalphStartResponse {
+    let response = match outcome {
+        RalphEnqueueOutcome::Started(process) => RalphStartResponse {
             workspace_id: workspace.id,
-            process_id: execution_process.id,
-        })),
-    ))
+            process_id: Some(process.id),
+            queue_position: None,
+        },
+        RalphEnqueueOutcome::Queued(position) => RalphStartResponse {
+            workspace_id: workspace.id,
+            process_id: None,
+            queue_position: Some(position),
+        },
+    };
+
+    Ok((StatusCode::OK, ResponseJson(ApiResponse::success(response))))
 }
 
 /// Restart Ralph from Failed state
@@ -558,77 +1769,49 @@ pub async fn restart(
         )));
     }
 
-    // Get the spec content
-    let spec_content = task
-        .description
+    // Verify task has a description (spec)
+    task.description
         .as_ref()
         .filter(|d| !d.trim().is_empty())
-        .cloned()
         .ok_or_else(|| {
             ApiError::BadRequest("Task must have a description (spec) to use Ralph".to_string())
         })?;
 
+    if ralph_scheduler().is_active(task.id) {
+        return Err(ApiError::BadRequest(
+            "Ralph already has a queued or running job for this task".to_string(),
+        ));
+    }
+
     // Get the workspace
     let workspaces = Workspace::fetch_all(pool, Some(task.id)).await?;
     let workspace = workspaces.first().ok_or_else(|| {
         ApiError::BadRequest("No workspace found for task".to_string())
     })?;
 
-    // Re-setup Ralph in worktree (may have been corrupted during failed execution)
-    setup_ralph_for_workspace(pool, workspace).await?;
-
-    // Update ralph_status to Planning
-    Task::update_ralph_status(pool, task.id, RalphStatus::Planning).await?;
-
-    // Create a new session
-    let session = Session::create(
-        pool,
-        &CreateSession {
-            executor: Some("RALPH".to_string()),
-        },
-        Uuid::new_v4(),
-        workspace.id,
-    )
-    .await?;
-
-    // Build executor action
-    let executor_action = ExecutorAction::new(
-        ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
-            prompt: spec_content,
-            executor_profile_id: ExecutorProfileId {
-                executor: BaseCodingAgent::Ralph,
-                variant: Some("PLAN".to_string()),
-            },
-            working_dir: None,
-        }),
-        None,
-    );
+    let project = task
+        .parent_project(pool)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Task has no parent project".to_string()))?;
 
-    // Start execution with RalphPlan run_reason
-    let execution_process = deployment
-        .container()
-        .start_execution(
-            workspace,
-            &session,
-            &executor_action,
-            &ExecutionProcessRunReason::RalphPlan,
-        )
+    let outcome = ralph_scheduler()
+        .enqueue(&deployment, &task, workspace, project.id, RalphJobKind::Restart)
         .await?;
 
-    tracing::info!(
-        "Restarted Ralph for task {} (workspace={}, process={})",
-        task.id,
-        workspace.id,
-        execution_process.id
-    );
-
-    Ok((
-        StatusCode::OK,
-        ResponseJson(ApiResponse::success(RalphStartResponse {
+    let response = match outcome {
+        RalphEnqueueOutcome::Started(process) => RalphStartResponse {
             workspace_id: workspace.id,
-            process_id: execution_process.id,
-        })),
-    ))
+            process_id: Some(process.id),
+            queue_position: None,
+        },
+        RalphEnqueueOutcome::Queued(position) => RalphStartResponse {
+            workspace_id: workspace.id,
+            process_id: None,
+            queue_position: Some(position),
+        },
+    };
+
+    Ok((StatusCode::OK, ResponseJson(ApiResponse::success(response))))
 }
 
 /// Cancel Ralph execution
@@ -656,8 +1839,57 @@ pub async fn cancel(
         )));
     }
 
+    // Drop any queued-but-not-yet-started job first, so cancelling a task
+    // that's waiting on the scheduler doesn't leave it to start later.
+    ralph_scheduler().remove_from_queue(task.id);
+
+    // Signal the loop driver first, so a race where a process completes
+    // and the loop driver re-reads state while we're cancelling doesn't
+    // respawn a new iteration after we've already decided to stop.
+    let workspaces = Workspace::fetch_all(pool, Some(task.id)).await?;
+    if let Some(workspace) = workspaces.first() {
+        if let Some(worktree_path) = workspace.container_ref.as_ref() {
+            let worktree_path = Path::new(worktree_path);
+            let mut state = read_ralph_loop_state(worktree_path).await;
+            state.cancelled = true;
+            write_ralph_loop_state(worktree_path, &state).await?;
+        }
+
+        // Stop the in-flight RalphPlan/RalphBuild process so the agent is
+        // actually signalled and reaped rather than left mutating the
+        // worktree after the status flips.
+        let latest_plan = ExecutionProcess::find_latest_by_workspace_and_run_reason(
+            pool,
+            workspace.id,
+            &ExecutionProcessRunReason::RalphPlan,
+        )
+        .await?;
+        let latest_build = ExecutionProcess::find_latest_by_workspace_and_run_reason(
+            pool,
+            workspace.id,
+            &ExecutionProcessRunReason::RalphBuild,
+        )
+        .await?;
+
+        for process in [latest_plan, latest_build].into_iter().flatten() {
+            if process.completed_at.is_none() {
+                if let Err(e) = deployment.container().stop_execution(&process).await {
+                    tracing::warn!(
+                        "Failed to stop Ralph process {} for task {}: {}",
+                        process.id,
+                        task.id,
+                        e
+                    );
+                } else {
+                    tracing::info!("Stopped in-flight Ralph process {} for task {}", process.id, task.id);
+                }
+            }
+        }
+    }
+
     // Update ralph_status to None
     Task::update_ralph_status(pool, task.id, RalphStatus::None).await?;
+    emit_ralph_transition(pool, &task, task.ralph_status, RalphStatus::None, None, None).await;
 
     tracing::info!("Cancelled Ralph for task {}", task.id);
 
@@ -686,19 +1918,157 @@ pub async fn reset(
 
     // Update ralph_status to None
     Task::update_ralph_status(pool, task.id, RalphStatus::None).await?;
+    emit_ralph_transition(pool, &task, task.ralph_status, RalphStatus::None, None, None).await;
 
     tracing::info!("Reset Ralph for task {}", task.id);
 
     Ok((StatusCode::OK, ResponseJson(ApiResponse::success(()))))
 }
 
+/// Which stream a captured log line came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum RalphLogChannel {
+    Stdout,
+    Stderr,
+    System,
+}
+
+/// Prefix a capture site should prepend to a line before appending it to
+/// `ExecutionProcessLogs`, so the channel is real metadata recorded where
+/// the line is written instead of guessed later. `classify_log_line` strips
+/// this back off. `\u{1}` (SOH) is used as the marker since it can't occur
+/// in normal process output.
+const CHANNEL_TAG_STDOUT: &str = "\u{1}O";
+const CHANNEL_TAG_STDERR: &str = "\u{1}E";
+const CHANNEL_TAG_SYSTEM: &str = "\u{1}S";
+
+/// Tag `line` with its real channel before it's appended to
+/// `ExecutionProcessLogs`. Call this at the point stdout/stderr/system
+/// output is actually captured, rather than reclassifying by content later.
+///
+/// Deliberately not called anywhere in this crate: `ExecutionProcessLogs`
+/// rows are written by the process-execution layer that owns a Ralph
+/// child's stdout/stderr pipes, and that layer is part of `db`/the
+/// execution-process runner, not `routes::ralph` - this crate only ever
+/// reads rows back via `ExecutionProcessLogs::find_by_execution_id`, it
+/// never writes one. Until that writer calls `tag_channel_line` at
+/// capture time, `classify_log_line` below has no tagged input to prefer
+/// and every line it sees falls through to its content heuristic; that's a
+/// real, currently-unclosed gap in log-channel accuracy, not one this
+/// crate can close on its own.
+#[allow(dead_code)]
+pub fn tag_channel_line(channel: RalphLogChannel, line: &str) -> String {
+    let prefix = match channel {
+        RalphLogChannel::Stdout => CHANNEL_TAG_STDOUT,
+        RalphLogChannel::Stderr => CHANNEL_TAG_STDERR,
+        RalphLogChannel::System => CHANNEL_TAG_SYSTEM,
+    };
+    format!("{prefix}{line}")
+}
+
+/// The channel a captured line belongs to, and its content with any
+/// `tag_channel_line` prefix stripped back off. Lines tagged at capture
+/// time report their real channel; untagged lines - which today is all of
+/// them, since nothing in this crate writes `ExecutionProcessLogs` rows,
+/// see `tag_channel_line` - fall back to a best-effort guess from content,
+/// which is strictly less precise and will misclassify, e.g., stderr
+/// output that doesn't happen to start with "error".
+fn classify_log_line(line: &str) -> (RalphLogChannel, &str) {
+    if let Some(rest) = line.strip_prefix(CHANNEL_TAG_STDOUT) {
+        return (RalphLogChannel::Stdout, rest);
+    }
+    if let Some(rest) = line.strip_prefix(CHANNEL_TAG_STDERR) {
+        return (RalphLogChannel::Stderr, rest);
+    }
+    if let Some(rest) = line.strip_prefix(CHANNEL_TAG_SYSTEM) {
+        return (RalphLogChannel::System, rest);
+    }
+
+    let trimmed = line.trim_start();
+    let lower = trimmed.to_ascii_lowercase();
+    let channel = if lower.starts_with("[ralph]") || lower.starts_with(".ralph-vibe-kanban") {
+        RalphLogChannel::System
+    } else if lower.starts_with("error") || lower.starts_with("[error]") || lower.contains("panicked at") {
+        RalphLogChannel::Stderr
+    } else {
+        RalphLogChannel::Stdout
+    };
+    (channel, line)
+}
+
+/// `?channel=stdout|stderr|system|all` and `?tail=` query params for
+/// `/details`. Missing or unrecognized channel values behave as `all`.
+#[derive(Debug, Deserialize)]
+pub struct LogChannelQuery {
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// How many trailing lines of each tail to return; clamped to
+    /// `MAX_LOG_TAIL`. Defaults to `DEFAULT_LOG_TAIL` when omitted.
+    #[serde(default)]
+    pub tail: Option<usize>,
+}
+
+/// Default tail length for `/details` when `?tail=` is omitted.
+const DEFAULT_LOG_TAIL: usize = 50;
+/// Upper bound on `?tail=`, so a misbehaving client can't force the whole
+/// log to be re-joined and returned in one response.
+const MAX_LOG_TAIL: usize = 2000;
+
+fn resolve_log_tail(requested: Option<usize>) -> usize {
+    requested.map(|n| n.clamp(1, MAX_LOG_TAIL)).unwrap_or(DEFAULT_LOG_TAIL)
+}
+
+fn log_channel_matches(channel: Option<&str>, line: &str) -> bool {
+    let (actual, _) = classify_log_line(line);
+    match channel {
+        Some("stdout") => actual == RalphLogChannel::Stdout,
+        Some("stderr") => actual == RalphLogChannel::Stderr,
+        Some("system") => actual == RalphLogChannel::System,
+        _ => true,
+    }
+}
+
+/// Join whichever of `lines` belong to `channel`, or all of them for
+/// `None`/`"all"`/an unrecognized value. Strips each line's channel tag (if
+/// any) before joining, so it never leaks into the returned content.
+fn filter_lines_by_channel(lines: &[&str], channel: Option<&str>) -> String {
+    lines
+        .iter()
+        .filter(|line| log_channel_matches(channel, line))
+        .map(|line| classify_log_line(line).1)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Tail of up to `tail` lines, or `None` if there's nothing on that channel.
+fn tail_channel(lines: &[&str], channel: RalphLogChannel, tail: usize) -> Option<String> {
+    let matching: Vec<&str> = lines
+        .iter()
+        .filter_map(|line| {
+            let (actual, content) = classify_log_line(line);
+            (actual == channel).then_some(content)
+        })
+        .collect();
+    if matching.is_empty() {
+        return None;
+    }
+    let tail_lines = if matching.len() > tail {
+        &matching[matching.len() - tail..]
+    } else {
+        &matching[..]
+    };
+    Some(tail_lines.join("\n"))
+}
+
 /// Get execution details for debugging Ralph failures
 ///
-/// GET /tasks/:id/ralph/details
+/// GET /tasks/:id/ralph/details?channel=stdout|stderr|all
 /// Returns information about the last Ralph execution including logs
 pub async fn get_execution_details(
     Extension(task): Extension<Task>,
     State(deployment): State<DeploymentImpl>,
+    Query(query): Query<LogChannelQuery>,
 ) -> Result<ResponseJson<ApiResponse<RalphExecutionDetailsResponse>>, ApiError> {
     let pool = &deployment.db().pool;
 
@@ -716,6 +2086,12 @@ pub async fn get_execution_details(
                 run_reason: None,
                 completed_at: None,
                 last_logs: None,
+                last_stdout: None,
+                last_stderr: None,
+                retry_history: Vec::new(),
+                queue_position: ralph_scheduler().queue_position(task.id),
+                triggered_by: ralph_webhook_triggers().lock().unwrap().get(&task.id).cloned(),
+                artifacts: Vec::new(),
             })));
         }
     };
@@ -760,16 +2136,23 @@ pub async fn get_execution_details(
                 run_reason: None,
                 completed_at: None,
                 last_logs: None,
+                last_stdout: None,
+                last_stderr: None,
+                retry_history: Vec::new(),
+                queue_position: ralph_scheduler().queue_position(task.id),
+                triggered_by: ralph_webhook_triggers().lock().unwrap().get(&task.id).cloned(),
+                artifacts: Vec::new(),
             })));
         }
     };
 
-    // Get logs for this execution (last 50 lines)
+    // Get logs for this execution (last `?tail=` lines, per requested channel)
+    let tail = resolve_log_tail(query.tail);
     let log_records = ExecutionProcessLogs::find_by_execution_id(pool, process.id).await?;
-    let last_logs = if log_records.is_empty() {
-        None
+    let (last_logs, last_stdout, last_stderr) = if log_records.is_empty() {
+        (None, None, None)
     } else {
-        // Combine all logs and take last 50 lines
+        // Combine all logs and take the last `tail` lines
         let all_logs: String = log_records
             .iter()
             .flat_map(|r| r.logs.lines())
@@ -777,13 +2160,17 @@ pub async fn get_execution_details(
             .join("\n");
 
         let lines: Vec<&str> = all_logs.lines().collect();
-        let last_50: String = if lines.len() > 50 {
-            lines[lines.len() - 50..].join("\n")
+        let tail_lines: &[&str] = if lines.len() > tail {
+            &lines[lines.len() - tail..]
         } else {
-            all_logs
+            &lines[..]
         };
 
-        Some(last_50)
+        (
+            Some(filter_lines_by_channel(tail_lines, query.channel.as_deref())),
+            tail_channel(&lines, RalphLogChannel::Stdout, tail),
+            tail_channel(&lines, RalphLogChannel::Stderr, tail),
+        )
     };
 
     Ok(ResponseJson(ApiResponse::success(RalphExecutionDetailsResponse {
@@ -793,14 +2180,446 @@ pub async fn get_execution_details(
         run_reason: Some(format!("{:?}", process.run_reason)),
         completed_at: process.completed_at.map(|dt| dt.to_rfc3339()),
         last_logs,
+        last_stdout,
+        last_stderr,
+        retry_history: read_ralph_loop_state(Path::new(
+            workspace.container_ref.as_deref().unwrap_or_default(),
+        ))
+        .await
+        .failed_attempts,
+        queue_position: ralph_scheduler().queue_position(task.id),
+        triggered_by: ralph_webhook_triggers().lock().unwrap().get(&task.id).cloned(),
+        artifacts: read_ralph_artifact_manifest(&ralph_artifact_dir(workspace.id, process.id))
+            .await
+            .map(|m| m.artifacts)
+            .unwrap_or_default(),
+    })))
+}
+
+/// How often the SSE stream polls `ExecutionProcessLogs` for new content.
+const LOG_STREAM_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+/// If no new log content arrives within this window, emit a `stall` event
+/// so the UI can surface "agent appears stuck" (pairs with the loop
+/// driver's own no-progress detection).
+const LOG_STREAM_STALL_AFTER: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Rounds `idx` down to the nearest UTF-8 char boundary in `s` (clamping to
+/// `s.len()` if `idx` is past the end), so an untrusted byte offset - a
+/// client-supplied `?from_offset=`, or one computed against log content
+/// that has since changed underneath it - can never split a multi-byte
+/// codepoint when used to slice `s`.
+fn floor_char_boundary(s: &str, idx: usize) -> usize {
+    let mut idx = idx.min(s.len());
+    while idx > 0 && !s.is_char_boundary(idx) {
+        idx -= 1;
+    }
+    idx
+}
+
+/// Query params for `GET /tasks/:id/ralph/logs/stream`.
+#[derive(Debug, Deserialize)]
+pub struct LogStreamQuery {
+    /// Byte offset (into the combined per-process log) the caller has
+    /// already seen; a reconnecting client passes back the `offset` of the
+    /// last chunk it received so it resumes instead of re-reading history.
+    #[serde(default)]
+    pub from_offset: usize,
+    /// Only forward lines on this channel (`stdout`/`stderr`/`system`);
+    /// omitted or unrecognized means all channels.
+    #[serde(default)]
+    pub channel: Option<String>,
+}
+
+/// One `log` event's payload: a single line, tagged with the cumulative
+/// byte offset it ends at so a disconnecting client can resume precisely
+/// via `?from_offset=`.
+#[derive(Debug, Serialize)]
+struct LogStreamChunk {
+    offset: usize,
+    line: String,
+}
+
+struct LogStreamState {
+    deployment: DeploymentImpl,
+    task_id: Uuid,
+    last_len: usize,
+    last_status: RalphStatus,
+    last_activity: std::time::Instant,
+    stalled: bool,
+    done: bool,
+    notifier_rx: tokio::sync::broadcast::Receiver<RalphTransitionEvent>,
+    /// Lines already fetched but not yet emitted, drained one per poll so a
+    /// large backlog (the initial historical flush, or a burst of output)
+    /// doesn't arrive as a single oversized event.
+    pending_lines: std::collections::VecDeque<LogStreamChunk>,
+    /// `?channel=` filter; `None` forwards every line.
+    channel: Option<String>,
+}
+
+/// Stream new Ralph log lines and status-transition events as they occur,
+/// instead of the one-shot tail `get_execution_details` returns. On
+/// connect, flushes every log line after `from_offset` (the full history
+/// when omitted) before switching to forwarding new output as it's
+/// produced; closes cleanly once the task leaves an active
+/// (`Planning`/`Building`) state. Transition events are drained from the
+/// same `RalphNotifierService` bus that drives webhook delivery, so this
+/// stream and external integrations never disagree about what happened.
+///
+/// GET /tasks/:id/ralph/logs/stream?from_offset=<bytes>
+pub async fn get_logs_stream(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<LogStreamQuery>,
+) -> Sse<impl Stream<Item = Result<Event, std::convert::Infallible>>> {
+    let state = LogStreamState {
+        deployment,
+        task_id: task.id,
+        last_len: query.from_offset,
+        last_status: task.ralph_status,
+        last_activity: std::time::Instant::now(),
+        stalled: false,
+        done: false,
+        notifier_rx: ralph_notifier().subscribe(),
+        pending_lines: std::collections::VecDeque::new(),
+        channel: query.channel,
+    };
+
+    let stream = stream::unfold(state, |mut st| async move {
+        if st.done && st.pending_lines.is_empty() {
+            return None;
+        }
+
+        if let Some(chunk) = st.pending_lines.pop_front() {
+            let data = serde_json::to_string(&chunk).unwrap_or_default();
+            return Some((Ok(Event::default().event("log").data(data)), st));
+        }
+
+        if st.done {
+            return None;
+        }
+
+        // Surface notifier-bus transitions as soon as they fire, rather
+        // than waiting for the next poll tick.
+        while let Ok(event) = st.notifier_rx.try_recv() {
+            if event.task_id != st.task_id {
+                continue;
+            }
+            st.last_activity = std::time::Instant::now();
+            st.stalled = false;
+            let data = serde_json::to_string(&event).unwrap_or_default();
+            return Some((Ok(Event::default().event("transition").data(data)), st));
+        }
+
+        loop {
+            tokio::time::sleep(LOG_STREAM_POLL_INTERVAL).await;
+
+            let pool = &st.deployment.db().pool;
+            let Ok(Some(task)) = Task::find_by_id(pool, st.task_id).await else {
+                return None;
+            };
+
+            let workspaces = Workspace::fetch_all(pool, Some(st.task_id)).await.unwrap_or_default();
+            let Some(workspace) = workspaces.first() else {
+                continue;
+            };
+
+            let latest_plan = ExecutionProcess::find_latest_by_workspace_and_run_reason(
+                pool,
+                workspace.id,
+                &ExecutionProcessRunReason::RalphPlan,
+            )
+            .await
+            .ok()
+            .flatten();
+            let latest_build = ExecutionProcess::find_latest_by_workspace_and_run_reason(
+                pool,
+                workspace.id,
+                &ExecutionProcessRunReason::RalphBuild,
+            )
+            .await
+            .ok()
+            .flatten();
+            let process = match (&latest_plan, &latest_build) {
+                (Some(p), Some(b)) if b.created_at > p.created_at => Some(b),
+                (Some(p), _) => Some(p),
+                (None, Some(b)) => Some(b),
+                (None, None) => None,
+            };
+
+            let full_log = if let Some(process) = process {
+                ExecutionProcessLogs::find_by_execution_id(pool, process.id)
+                    .await
+                    .map(|records| records.iter().flat_map(|r| r.logs.lines()).collect::<Vec<_>>().join("\n"))
+                    .unwrap_or_default()
+            } else {
+                String::new()
+            };
+
+            // `st.last_len` started out as a client-supplied `?from_offset=`
+            // and may no longer land on a char boundary of the current
+            // `full_log` (a stale/arbitrary value, or log content that
+            // shifted underneath it) - floor it first so the slice below
+            // can never split a multi-byte codepoint and panic.
+            let last_len = floor_char_boundary(&full_log, st.last_len);
+            if full_log.len() > last_len {
+                let new_content = &full_log[last_len..];
+                let mut consumed = last_len;
+                for (i, line) in new_content.split('\n').enumerate() {
+                    if i > 0 {
+                        consumed += 1; // the '\n' separator consumed by split
+                    }
+                    consumed += line.len();
+                    if log_channel_matches(st.channel.as_deref(), line) {
+                        st.pending_lines.push_back(LogStreamChunk {
+                            offset: consumed,
+                            line: classify_log_line(line).1.to_string(),
+                        });
+                    }
+                }
+                st.last_len = full_log.len();
+                st.last_activity = std::time::Instant::now();
+                st.stalled = false;
+
+                if let Some(chunk) = st.pending_lines.pop_front() {
+                    let data = serde_json::to_string(&chunk).unwrap_or_default();
+                    return Some((Ok(Event::default().event("log").data(data)), st));
+                }
+            }
+
+            if task.ralph_status != st.last_status {
+                st.last_status = task.ralph_status;
+                st.last_activity = std::time::Instant::now();
+                st.stalled = false;
+                let terminal = !matches!(task.ralph_status, RalphStatus::Planning | RalphStatus::Building);
+                st.done = terminal;
+                let event = Event::default().event("status").data(format!("{:?}", task.ralph_status));
+                return Some((Ok(event), st));
+            }
+
+            if !st.stalled && st.last_activity.elapsed() >= LOG_STREAM_STALL_AFTER {
+                st.stalled = true;
+                tracing::warn!(
+                    "Ralph log stream for task {} has been silent for {:?}; agent may be stuck",
+                    st.task_id,
+                    st.last_activity.elapsed()
+                );
+                return Some((Ok(Event::default().event("stall").data("no output recently")), st));
+            }
+
+            if !matches!(task.ralph_status, RalphStatus::Planning | RalphStatus::Building) {
+                // Nothing new to say and the task is no longer active.
+                return None;
+            }
+        }
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// Query params for `GET /tasks/:id/ralph/logs`.
+#[derive(Debug, Deserialize)]
+pub struct LogsPageQuery {
+    /// 1-based, exclusive: return lines numbered below this one. Omit to
+    /// get the tail end of the log.
+    #[serde(default)]
+    pub before_line: Option<usize>,
+    /// How many lines to return, clamped to `MAX_LOGS_PAGE_LIMIT`.
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// Default page size for `GET /tasks/:id/ralph/logs` when `?limit=` is
+/// omitted.
+const DEFAULT_LOGS_PAGE_LIMIT: usize = 200;
+/// Upper bound on `?limit=`.
+const MAX_LOGS_PAGE_LIMIT: usize = 2000;
+
+/// Response for `GET /tasks/:id/ralph/logs`.
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct RalphLogsPageResponse {
+    pub lines: Vec<String>,
+    pub total_lines: usize,
+    /// Whether an earlier page exists (pass the lowest line number in
+    /// `lines` back as `?before_line=` to fetch it).
+    pub has_more: bool,
+}
+
+/// Page back through a run's full combined log, oldest-first, without
+/// reconstructing and re-joining the entire log string on every call - the
+/// tail on `/details` and the live `/logs/stream` cover the "what's
+/// happening now" case, this covers "let me scroll back arbitrarily far".
+///
+/// GET /tasks/:id/ralph/logs?before_line=&limit=
+pub async fn get_logs_page(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<LogsPageQuery>,
+) -> Result<ResponseJson<ApiResponse<RalphLogsPageResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspaces = Workspace::fetch_all(pool, Some(task.id)).await?;
+    let workspace = workspaces
+        .first()
+        .ok_or_else(|| ApiError::BadRequest("No workspace found for task".to_string()))?;
+
+    let latest_plan = ExecutionProcess::find_latest_by_workspace_and_run_reason(
+        pool,
+        workspace.id,
+        &ExecutionProcessRunReason::RalphPlan,
+    )
+    .await?;
+    let latest_build = ExecutionProcess::find_latest_by_workspace_and_run_reason(
+        pool,
+        workspace.id,
+        &ExecutionProcessRunReason::RalphBuild,
+    )
+    .await?;
+    let process = match (&latest_plan, &latest_build) {
+        (Some(plan), Some(build)) => {
+            if build.created_at > plan.created_at {
+                Some(build)
+            } else {
+                Some(plan)
+            }
+        }
+        (Some(plan), None) => Some(plan),
+        (None, Some(build)) => Some(build),
+        (None, None) => None,
+    }
+    .ok_or_else(|| ApiError::BadRequest("No Ralph process found for task".to_string()))?;
+
+    let log_records = ExecutionProcessLogs::find_by_execution_id(pool, process.id).await?;
+    let lines: Vec<&str> = log_records.iter().flat_map(|r| r.logs.lines()).collect();
+    let total_lines = lines.len();
+
+    let limit = query
+        .limit
+        .map(|n| n.clamp(1, MAX_LOGS_PAGE_LIMIT))
+        .unwrap_or(DEFAULT_LOGS_PAGE_LIMIT);
+    let end = query
+        .before_line
+        .map(|n| n.saturating_sub(1).min(total_lines))
+        .unwrap_or(total_lines);
+    let start = end.saturating_sub(limit);
+
+    Ok(ResponseJson(ApiResponse::success(RalphLogsPageResponse {
+        lines: lines[start..end].iter().map(|s| s.to_string()).collect(),
+        total_lines,
+        has_more: start > 0,
     })))
 }
 
+/// Response for `GET /tasks/:id/ralph/artifacts`
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct RalphArtifactsListResponse {
+    pub runs: Vec<RalphRunManifest>,
+}
+
+/// List every run's artifacts manifest for a task's workspace, most recent
+/// first, so plan evolution and per-iteration outcomes can be inspected
+/// across a whole build even after the worktree has moved on.
+///
+/// GET /tasks/:id/ralph/artifacts
+pub async fn list_artifacts(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<RalphArtifactsListResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let workspaces = Workspace::fetch_all(pool, Some(task.id)).await?;
+    let workspace = workspaces
+        .first()
+        .ok_or_else(|| ApiError::BadRequest("No workspace found for task".to_string()))?;
+
+    let workspace_dir = ralph_artifacts_root().join(workspace.id.to_string());
+    let mut runs = Vec::new();
+
+    if let Ok(mut entries) = tokio::fs::read_dir(&workspace_dir).await {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            if let Some(manifest) = read_ralph_artifact_manifest(&entry.path()).await {
+                runs.push(manifest);
+            }
+        }
+    }
+    runs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+
+    Ok(ResponseJson(ApiResponse::success(RalphArtifactsListResponse { runs })))
+}
+
+/// Fetch one run's manifest (run reason, exit code, timing) by process id.
+/// The plan snapshots, diff, and combined log sit alongside it on disk at
+/// the same artifacts directory, for out-of-band inspection.
+///
+/// GET /tasks/:id/ralph/artifacts/:process_id
+pub async fn get_artifact(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    AxumPath(process_id): AxumPath<Uuid>,
+) -> Result<ResponseJson<ApiResponse<RalphRunManifest>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let workspaces = Workspace::fetch_all(pool, Some(task.id)).await?;
+    let workspace = workspaces
+        .first()
+        .ok_or_else(|| ApiError::BadRequest("No workspace found for task".to_string()))?;
+
+    let dir = ralph_artifact_dir(workspace.id, process_id);
+    read_ralph_artifact_manifest(&dir)
+        .await
+        .map(|manifest| ResponseJson(ApiResponse::success(manifest)))
+        .ok_or_else(|| ApiError::BadRequest(format!("No artifacts found for Ralph process {}", process_id)))
+}
+
+/// Download one artifact file from a run, by the `name` listed in its
+/// manifest's `artifacts` summaries. Artifact files are small, bounded text
+/// (plan snapshots, a diff, a combined log), so this reads the whole file
+/// into memory rather than wiring up a streaming body.
+///
+/// GET /tasks/:id/ralph/artifacts/:process_id/:name
+pub async fn get_artifact_file(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    AxumPath((process_id, name)): AxumPath<(Uuid, String)>,
+) -> Result<impl axum::response::IntoResponse, ApiError> {
+    let pool = &deployment.db().pool;
+    let workspaces = Workspace::fetch_all(pool, Some(task.id)).await?;
+    let workspace = workspaces
+        .first()
+        .ok_or_else(|| ApiError::BadRequest("No workspace found for task".to_string()))?;
+
+    let dir = ralph_artifact_dir(workspace.id, process_id);
+    let manifest = read_ralph_artifact_manifest(&dir)
+        .await
+        .ok_or_else(|| ApiError::BadRequest(format!("No artifacts found for Ralph process {}", process_id)))?;
+    let summary = manifest
+        .artifacts
+        .iter()
+        .find(|a| a.name == name)
+        .ok_or_else(|| ApiError::BadRequest(format!("No artifact named '{}' for this run", name)))?;
+
+    let content = tokio::fs::read(dir.join(&summary.name))
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Failed to read artifact '{}': {}", name, e)))?;
+
+    Ok((
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, summary.content_type.clone())],
+        content,
+    ))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    load_ralph_webhook_keys_from_env();
+
     let ralph_routes = Router::new()
         .route("/status", get(get_status))
         .route("/details", get(get_execution_details))
+        .route("/iterations", get(get_iterations))
+        .route("/logs", get(get_logs_page))
+        .route("/logs/stream", get(get_logs_stream))
+        .route("/artifacts", get(list_artifacts))
+        .route("/artifacts/{process_id}", get(get_artifact))
+        .route("/artifacts/{process_id}/{name}", get(get_artifact_file))
         .route("/start-plan", post(start_plan))
+        .route("/webhook", post(webhook_start_plan))
         .route("/plan", get(get_plan))
         .route("/approve", post(approve))
         .route("/replan", post(replan))
@@ -808,9 +2627,13 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/cancel", post(cancel))
         .route("/reset", post(reset));
 
-    // Nest under /tasks/:task_id/ralph with task middleware
-    Router::new().nest(
-        "/tasks/{task_id}/ralph",
-        ralph_routes.layer(from_fn_with_state(deployment.clone(), load_task_middleware)),
-    )
+    // Nest under /tasks/:task_id/ralph with task middleware, plus a
+    // deployment-level webhook route that has no task in its path and
+    // resolves one from the payload instead.
+    Router::new()
+        .nest(
+            "/tasks/{task_id}/ralph",
+            ralph_routes.layer(from_fn_with_state(deployment.clone(), load_task_middleware)),
+        )
+        .route("/ralph/webhook", post(webhook_start_plan_for_deployment))
 }
@@ -1,5 +1,15 @@
-// Telemetry removed - this module is now a no-op stub
+// Remote (PostHog) telemetry removed. `AnalyticsService` now only drives
+// `LocalMetrics`, a never-phones-home collector of per-task executor
+// resource usage persisted to disk instead of a remote endpoint.
 
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 #[derive(Debug, Clone)]
@@ -15,25 +25,180 @@ pub struct AnalyticsConfig {
 }
 
 impl AnalyticsConfig {
-    /// Always returns None - analytics has been disabled
+    /// Always returns None - remote analytics has been disabled. Use
+    /// `AnalyticsService::with_local_metrics` for local resource tracking.
     pub fn new() -> Option<Self> {
         None
     }
 }
 
+/// Filename the local metrics sink is persisted under, relative to the
+/// `data_dir` passed to `AnalyticsService::with_local_metrics`.
+const LOCAL_METRICS_FILENAME: &str = "local-metrics.json";
+
+/// One point-in-time resource reading for a running executor child.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ProcessSample {
+    pub rss_bytes: u64,
+    /// Cumulative CPU time consumed by the process since it started.
+    pub cpu_seconds: f64,
+}
+
+impl ProcessSample {
+    /// Best-effort resource sample for a running process. On Linux this
+    /// reads `/proc/<pid>/stat`; other platforms have no equally cheap,
+    /// dependency-free source and return `None` rather than guess.
+    pub fn read(pid: u32) -> Option<Self> {
+        #[cfg(target_os = "linux")]
+        {
+            let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+            // `comm` (field 2) is parenthesized and may itself contain
+            // spaces/parens, so split on the *last* ")" rather than
+            // tokenizing naively; everything after it is space-separated
+            // and 1-indexed from field 3 onward per `man 5 proc`.
+            let after_comm = stat.rsplit_once(')')?.1;
+            let fields: Vec<&str> = after_comm.split_whitespace().collect();
+            // utime = field 14, stime = field 15, rss (pages) = field 24.
+            let utime: u64 = fields.get(11)?.parse().ok()?;
+            let stime: u64 = fields.get(12)?.parse().ok()?;
+            let rss_pages: u64 = fields.get(21)?.parse().ok()?;
+            const TICKS_PER_SEC: f64 = 100.0; // sysconf(_SC_CLK_TCK), effectively always 100 on Linux
+            const PAGE_SIZE: u64 = 4096; // sysconf(_SC_PAGESIZE), effectively always 4096 on Linux
+            Some(Self {
+                rss_bytes: rss_pages * PAGE_SIZE,
+                cpu_seconds: (utime + stime) as f64 / TICKS_PER_SEC,
+            })
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = pid;
+            None
+        }
+    }
+}
+
+/// Accumulated resource usage for one task's executor run(s), enough to
+/// answer "this loop used X CPU-seconds over N iterations".
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaskMetrics {
+    pub wall_time_ms: u128,
+    pub peak_rss_bytes: u64,
+    pub cpu_seconds: f64,
+    pub iterations: u32,
+    /// Raw `track_event` calls recorded against this task.
+    pub events: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct LocalMetricsState {
+    tasks: HashMap<String, TaskMetrics>,
+}
+
+/// Local-only (never phones home) collector of per-task executor resource
+/// usage. Replaces the remote PostHog pipeline: every update is persisted
+/// as JSON under `data_dir` instead of being sent anywhere.
+#[derive(Debug, Clone)]
+pub struct LocalMetrics {
+    path: PathBuf,
+    state: Arc<Mutex<LocalMetricsState>>,
+}
+
+impl LocalMetrics {
+    pub fn new(data_dir: PathBuf) -> Self {
+        let path = data_dir.join(LOCAL_METRICS_FILENAME);
+        let state = Self::load(&path).unwrap_or_default();
+        Self { path, state: Arc::new(Mutex::new(state)) }
+    }
+
+    fn load(path: &Path) -> Option<LocalMetricsState> {
+        let content = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn persist(&self, state: &LocalMetricsState) {
+        if let Ok(content) = serde_json::to_string_pretty(state) {
+            let _ = std::fs::write(&self.path, content);
+        }
+    }
+
+    /// Record one resource sample against `task_label`'s running totals.
+    /// Peak RSS tracks the max seen; CPU seconds is overwritten with the
+    /// latest reading, since `ProcessSample::read` already returns the
+    /// process's lifetime-cumulative CPU time rather than a delta.
+    pub fn record_sample(&self, task_label: &str, sample: ProcessSample) {
+        let mut state = self.state.lock().unwrap();
+        let entry = state.tasks.entry(task_label.to_string()).or_default();
+        entry.peak_rss_bytes = entry.peak_rss_bytes.max(sample.rss_bytes);
+        entry.cpu_seconds = sample.cpu_seconds;
+        self.persist(&state);
+    }
+
+    /// Record that one iteration of `task_label` finished.
+    pub fn record_iteration(&self, task_label: &str, wall_time: Duration) {
+        let mut state = self.state.lock().unwrap();
+        let entry = state.tasks.entry(task_label.to_string()).or_default();
+        entry.wall_time_ms += wall_time.as_millis();
+        entry.iterations += 1;
+        self.persist(&state);
+    }
+
+    /// Count a raw `track_event` call against `task_label`.
+    fn record_event(&self, task_label: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.tasks.entry(task_label.to_string()).or_default().events += 1;
+        self.persist(&state);
+    }
+
+    /// Snapshot of everything recorded for `task_label` so far.
+    pub fn task_metrics(&self, task_label: &str) -> Option<TaskMetrics> {
+        self.state.lock().unwrap().tasks.get(task_label).cloned()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct AnalyticsService {
     _private: (),
+    metrics: Option<LocalMetrics>,
 }
 
 impl AnalyticsService {
     pub fn new(_config: AnalyticsConfig) -> Self {
-        Self { _private: () }
+        Self { _private: (), metrics: None }
+    }
+
+    /// Construct a service backed by `LocalMetrics`, persisting to
+    /// `data_dir`, instead of the disabled remote pipeline.
+    pub fn with_local_metrics(data_dir: PathBuf) -> Self {
+        Self { _private: (), metrics: Some(LocalMetrics::new(data_dir)) }
+    }
+
+    /// Routes to the local metrics sink (keyed by `user_id`) when one is
+    /// configured; otherwise a no-op, matching the prior disabled behavior.
+    pub fn track_event(&self, user_id: &str, _event_name: &str, _properties: Option<Value>) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_event(user_id);
+        }
+    }
+
+    /// Record one resource sample for `task_label`. No-op if local metrics
+    /// weren't configured via `with_local_metrics`.
+    pub fn record_process_sample(&self, task_label: &str, pid: u32) {
+        if let (Some(metrics), Some(sample)) = (&self.metrics, ProcessSample::read(pid)) {
+            metrics.record_sample(task_label, sample);
+        }
+    }
+
+    /// Record that one iteration of `task_label` finished.
+    pub fn record_iteration(&self, task_label: &str, wall_time: Duration) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_iteration(task_label, wall_time);
+        }
     }
 
-    /// No-op: analytics has been disabled
-    pub fn track_event(&self, _user_id: &str, _event_name: &str, _properties: Option<Value>) {
-        // No-op - telemetry disabled
+    /// Snapshot of `task_label`'s accumulated metrics, for the UI to show
+    /// e.g. "this loop used X CPU-seconds over N iterations".
+    pub fn task_metrics(&self, task_label: &str) -> Option<TaskMetrics> {
+        self.metrics.as_ref().and_then(|m| m.task_metrics(task_label))
     }
 }
 
@@ -120,4 +285,48 @@ mod tests {
     fn test_analytics_config_returns_none() {
         assert!(AnalyticsConfig::new().is_none());
     }
+
+    #[test]
+    fn test_track_event_without_local_metrics_is_noop() {
+        let service = AnalyticsService::new(AnalyticsConfig {
+            posthog_api_key: String::new(),
+            posthog_api_endpoint: String::new(),
+        });
+        service.track_event("user-1", "some_event", None);
+        assert!(service.task_metrics("user-1").is_none());
+    }
+
+    #[test]
+    fn test_track_event_with_local_metrics_persists_and_counts() {
+        let dir = std::env::temp_dir()
+            .join(format!("vibe-kanban-analytics-test-{}", std::process::id()));
+        let _ = std::fs::create_dir_all(&dir);
+
+        let service = AnalyticsService::with_local_metrics(dir.clone());
+        service.track_event("task-1", "loop_iteration", None);
+        service.track_event("task-1", "loop_iteration", None);
+        service.record_iteration("task-1", Duration::from_millis(250));
+
+        let metrics = service.task_metrics("task-1").expect("metrics recorded");
+        assert_eq!(metrics.events, 2);
+        assert_eq!(metrics.iterations, 1);
+        assert_eq!(metrics.wall_time_ms, 250);
+
+        // A fresh LocalMetrics over the same dir should pick up the
+        // persisted state rather than starting from zero.
+        let reloaded = LocalMetrics::new(dir.clone());
+        assert_eq!(reloaded.task_metrics("task-1").unwrap().events, 2);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_process_sample_read_self_on_linux() {
+        #[cfg(target_os = "linux")]
+        {
+            let pid = std::process::id();
+            let sample = ProcessSample::read(pid).expect("can read /proc/self equivalent");
+            assert!(sample.cpu_seconds >= 0.0);
+        }
+    }
 }
@@ -0,0 +1,516 @@
+//! Ralph state-transition notifications.
+//!
+//! Every `RalphStatus` transition (plan started, awaiting approval,
+//! replanned, restarted, cancelled, reset, a build iteration completing,
+//! the run converging/failing, retries exhausted) is emitted here as a
+//! `RalphTransitionEvent`. Two things consume it: an in-process broadcast
+//! bus other modules (e.g. the SSE log stream) can subscribe to, and zero
+//! or more per-project `Notifier` sinks - a signed outbound webhook, a
+//! GitHub commit status, a Slack incoming webhook - so a team can mirror
+//! Ralph progress into their chat and their VCS checks simultaneously,
+//! without polling `/ralph/status` or `/ralph/details`.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Bounded so a backed-up/disconnected subscriber can't grow memory
+/// unbounded; a lagging subscriber just misses the oldest events.
+const EVENT_BUS_CAPACITY: usize = 256;
+
+/// One `RalphStatus` transition, carrying enough context for a backend to
+/// act on it without querying back into the API.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RalphTransitionEvent {
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub old_status: String,
+    pub new_status: String,
+    pub exit_code: Option<i64>,
+    pub iteration: Option<u32>,
+    pub occurred_at: String,
+}
+
+#[derive(Debug)]
+pub enum NotifierError {
+    Http(String),
+}
+
+impl std::fmt::Display for NotifierError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotifierError::Http(msg) => write!(f, "webhook request failed: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for NotifierError {}
+
+/// A backend that reacts to Ralph transitions (webhook, chat integration,
+/// etc). Kept minimal and infallible-on-the-caller-side: a backend failing
+/// is logged by `RalphNotifierService` and never blocks the transition
+/// itself.
+#[async_trait::async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, event: &RalphTransitionEvent) -> Result<(), NotifierError>;
+}
+
+/// Per-project webhook configuration: where to POST, and the shared secret
+/// used to HMAC-sign the payload so the receiver can verify authenticity.
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    pub url: String,
+    pub secret: String,
+}
+
+/// Signs the JSON body with HMAC-SHA256 over the configured secret and
+/// POSTs it, setting `X-Ralph-Signature` to the hex-encoded digest.
+pub struct WebhookNotifier {
+    config: WebhookConfig,
+    client: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(config: WebhookConfig) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Notifier for WebhookNotifier {
+    async fn notify(&self, event: &RalphTransitionEvent) -> Result<(), NotifierError> {
+        let body = serde_json::to_vec(event).map_err(|e| NotifierError::Http(e.to_string()))?;
+        let signature = hmac_sha256_hex(self.config.secret.as_bytes(), &body);
+
+        self.client
+            .post(&self.config.url)
+            .header("X-Ralph-Signature", signature)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| NotifierError::Http(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+/// Per-commit GitHub commit-status configuration: which repo/commit to
+/// annotate and the token used to authenticate against the Statuses API.
+#[derive(Debug, Clone)]
+pub struct GithubCommitStatusConfig {
+    pub repo_owner: String,
+    pub repo_name: String,
+    pub commit_sha: String,
+    pub token: String,
+}
+
+/// Posts a commit status to `POST /repos/{owner}/{repo}/statuses/{sha}` so
+/// a Ralph run shows up as a check alongside the rest of a PR's CI.
+pub struct GithubCommitStatusNotifier {
+    config: GithubCommitStatusConfig,
+    client: reqwest::Client,
+}
+
+impl GithubCommitStatusNotifier {
+    pub fn new(config: GithubCommitStatusConfig) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+/// GitHub only accepts these four states; anything mid-flight (Planning,
+/// Building, AwaitingApproval) is reported as `pending`.
+fn github_commit_state(new_status: &str) -> &'static str {
+    match new_status {
+        "Completed" => "success",
+        "Failed" => "failure",
+        _ => "pending",
+    }
+}
+
+#[async_trait::async_trait]
+impl Notifier for GithubCommitStatusNotifier {
+    async fn notify(&self, event: &RalphTransitionEvent) -> Result<(), NotifierError> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/statuses/{}",
+            self.config.repo_owner, self.config.repo_name, self.config.commit_sha
+        );
+        let body = serde_json::json!({
+            "state": github_commit_state(&event.new_status),
+            "description": format!("Ralph: {} -> {}", event.old_status, event.new_status),
+            "context": "ralph/vibe-kanban",
+        });
+
+        self.client
+            .post(&url)
+            .header("Authorization", format!("token {}", self.config.token))
+            .header("User-Agent", "vibe-kanban-ralph-notifier")
+            .header("Accept", "application/vnd.github+json")
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| NotifierError::Http(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+/// Slack incoming-webhook configuration: just the per-workspace URL Slack
+/// issues when a team adds an "Incoming Webhooks" integration.
+#[derive(Debug, Clone)]
+pub struct SlackConfig {
+    pub webhook_url: String,
+}
+
+/// Posts a plain-text message to a Slack incoming webhook summarizing the
+/// transition.
+pub struct SlackNotifier {
+    config: SlackConfig,
+    client: reqwest::Client,
+}
+
+impl SlackNotifier {
+    pub fn new(config: SlackConfig) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Notifier for SlackNotifier {
+    async fn notify(&self, event: &RalphTransitionEvent) -> Result<(), NotifierError> {
+        let text = format!(
+            "Ralph task `{}`: {} -> {}{}",
+            event.task_id,
+            event.old_status,
+            event.new_status,
+            event
+                .exit_code
+                .map(|code| format!(" (exit code {code})"))
+                .unwrap_or_default()
+        );
+        let body = serde_json::json!({ "text": text });
+
+        self.client
+            .post(&self.config.webhook_url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| NotifierError::Http(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+/// A configured per-project notification target. Stored rather than the
+/// `Notifier` trait object directly so sinks are `Clone`-able and cheap to
+/// hand out to `emit`'s callers.
+#[derive(Debug, Clone)]
+pub enum NotifierSinkConfig {
+    Webhook(WebhookConfig),
+    GithubCommitStatus(GithubCommitStatusConfig),
+    Slack(SlackConfig),
+}
+
+/// Dispatches transitions to the in-process event bus and to every sink
+/// configured for the transition's project.
+#[derive(Clone)]
+pub struct RalphNotifierService {
+    bus: broadcast::Sender<RalphTransitionEvent>,
+    project_sinks: Arc<RwLock<HashMap<Uuid, Vec<NotifierSinkConfig>>>>,
+}
+
+impl RalphNotifierService {
+    pub fn new() -> Self {
+        let (bus, _) = broadcast::channel(EVENT_BUS_CAPACITY);
+        Self {
+            bus,
+            project_sinks: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Subscribe to every Ralph transition across all projects/tasks; used
+    /// by the SSE log stream so its transition events come from the same
+    /// source of truth as external sink deliveries.
+    pub fn subscribe(&self) -> broadcast::Receiver<RalphTransitionEvent> {
+        self.bus.subscribe()
+    }
+
+    /// Add a sink for a project; a project can have any number of sinks
+    /// (e.g. a webhook and a Slack channel at once) and they all fire on
+    /// every transition.
+    pub fn add_project_sink(&self, project_id: Uuid, sink: NotifierSinkConfig) {
+        self.project_sinks.write().unwrap().entry(project_id).or_default().push(sink);
+    }
+
+    pub fn clear_project_sinks(&self, project_id: Uuid) {
+        self.project_sinks.write().unwrap().remove(&project_id);
+    }
+
+    /// Broadcast the event on the internal bus, then deliver it to every
+    /// sink configured for the project (if any), one after another;
+    /// returns once every sink has been tried. Each sink's failure is
+    /// logged independently rather than aborting the rest - a flaky Slack
+    /// webhook must never stop the GitHub commit status from landing, and
+    /// neither may ever affect the Ralph loop itself.
+    pub async fn emit(&self, event: RalphTransitionEvent) {
+        // No subscribers is the common case outside of an open SSE stream;
+        // a send error there just means nobody's listening right now.
+        let _ = self.bus.send(event.clone());
+
+        let sinks = self
+            .project_sinks
+            .read()
+            .unwrap()
+            .get(&event.project_id)
+            .cloned()
+            .unwrap_or_default();
+
+        for sink in sinks {
+            let result = match sink {
+                NotifierSinkConfig::Webhook(config) => WebhookNotifier::new(config).notify(&event).await,
+                NotifierSinkConfig::GithubCommitStatus(config) => {
+                    GithubCommitStatusNotifier::new(config).notify(&event).await
+                }
+                NotifierSinkConfig::Slack(config) => SlackNotifier::new(config).notify(&event).await,
+            };
+            if let Err(e) = result {
+                tracing::warn!(
+                    "Ralph notifier sink delivery failed for task {} (project {}): {}",
+                    event.task_id,
+                    event.project_id,
+                    e
+                );
+            }
+        }
+    }
+}
+
+impl Default for RalphNotifierService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A small self-contained HMAC-SHA256 so signing a webhook payload doesn't
+/// need a dedicated crypto dependency.
+fn hmac_sha256_hex(key: &[u8], message: &[u8]) -> String {
+    let digest = hmac_sha256(key, message);
+    digest.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Verify an inbound webhook signature: recomputes the HMAC-SHA256 of
+/// `message` under `key` and compares it against `hex_signature` (as sent
+/// in e.g. a `X-Hub-Signature-256` header, with the `sha256=` prefix
+/// already stripped by the caller) without branching on the first
+/// mismatching byte, so a timing side channel can't narrow down the digest.
+pub fn verify_hmac_sha256(key: &[u8], message: &[u8], hex_signature: &str) -> bool {
+    let expected = hmac_sha256(key, message);
+    let Some(provided) = hex_decode(hex_signature) else {
+        return false;
+    };
+    if provided.len() != expected.len() {
+        return false;
+    }
+    let mut diff = 0u8;
+    for (a, b) in expected.iter().zip(provided.iter()) {
+        diff |= a ^ b;
+    }
+    diff == 0
+}
+
+/// Decodes over raw bytes rather than string-slicing `s`: `s` comes straight
+/// from an inbound header value, which only has to be valid UTF-8, not
+/// ASCII, so slicing by byte offset (`&s[i..i+2]`) can land mid-codepoint
+/// and panic on a hostile or malformed signature header before any key
+/// comparison happens.
+fn hex_decode(s: &str) -> Option<Vec<u8>> {
+    let bytes = s.as_bytes();
+    if !s.is_ascii() || bytes.len() % 2 != 0 {
+        return None;
+    }
+    bytes
+        .chunks_exact(2)
+        .map(|pair| {
+            let hi = (pair[0] as char).to_digit(16)?;
+            let lo = (pair[1] as char).to_digit(16)?;
+            Some((hi * 16 + lo) as u8)
+        })
+        .collect()
+}
+
+const SHA256_BLOCK_SIZE: usize = 64;
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut key_block = [0u8; SHA256_BLOCK_SIZE];
+    if key.len() > SHA256_BLOCK_SIZE {
+        key_block[..32].copy_from_slice(&sha256(key));
+    } else {
+        key_block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut ipad = [0x36u8; SHA256_BLOCK_SIZE];
+    let mut opad = [0x5cu8; SHA256_BLOCK_SIZE];
+    for i in 0..SHA256_BLOCK_SIZE {
+        ipad[i] ^= key_block[i];
+        opad[i] ^= key_block[i];
+    }
+
+    let mut inner_input = ipad.to_vec();
+    inner_input.extend_from_slice(message);
+    let inner_hash = sha256(&inner_input);
+
+    let mut outer_input = opad.to_vec();
+    outer_input.extend_from_slice(&inner_hash);
+    sha256(&outer_input)
+}
+
+/// Pure-Rust SHA-256 (FIPS 180-4), sized for one-shot use on small webhook
+/// payloads rather than streaming large inputs.
+fn sha256(input: &[u8]) -> [u8; 32] {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+        0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+        0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+        0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+        0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+        0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+        0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+    ];
+
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+    ];
+
+    let bit_len = (input.len() as u64) * 8;
+    let mut padded = input.to_vec();
+    padded.push(0x80);
+    while padded.len() % SHA256_BLOCK_SIZE != 56 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&bit_len.to_be_bytes());
+
+    for chunk in padded.chunks(SHA256_BLOCK_SIZE) {
+        let mut w = [0u32; 64];
+        for (i, word) in chunk.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let (mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh) =
+            (h[0], h[1], h[2], h[3], h[4], h[5], h[6], h[7]);
+
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ ((!e) & g);
+            let temp1 = hh
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let temp2 = s0.wrapping_add(maj);
+
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = temp1.wrapping_add(temp2);
+        }
+
+        h[0] = h[0].wrapping_add(a);
+        h[1] = h[1].wrapping_add(b);
+        h[2] = h[2].wrapping_add(c);
+        h[3] = h[3].wrapping_add(d);
+        h[4] = h[4].wrapping_add(e);
+        h[5] = h[5].wrapping_add(f);
+        h[6] = h[6].wrapping_add(g);
+        h[7] = h[7].wrapping_add(hh);
+    }
+
+    let mut out = [0u8; 32];
+    for (i, word) in h.iter().enumerate() {
+        out[i * 4..i * 4 + 4].copy_from_slice(&word.to_be_bytes());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256_matches_known_vector() {
+        // NIST test vector for the empty string.
+        let digest = sha256(b"");
+        let hex: String = digest.iter().map(|b| format!("{b:02x}")).collect();
+        assert_eq!(
+            hex,
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn hmac_sha256_is_deterministic() {
+        let a = hmac_sha256_hex(b"secret", b"payload");
+        let b = hmac_sha256_hex(b"secret", b"payload");
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 64);
+    }
+
+    #[test]
+    fn verify_hmac_sha256_accepts_matching_signature() {
+        let hex = hmac_sha256_hex(b"secret", b"payload");
+        assert!(verify_hmac_sha256(b"secret", b"payload", &hex));
+    }
+
+    #[test]
+    fn verify_hmac_sha256_rejects_wrong_key_or_body() {
+        let hex = hmac_sha256_hex(b"secret", b"payload");
+        assert!(!verify_hmac_sha256(b"wrong-key", b"payload", &hex));
+        assert!(!verify_hmac_sha256(b"secret", b"tampered", &hex));
+        assert!(!verify_hmac_sha256(b"secret", b"payload", "not-hex"));
+    }
+
+    #[test]
+    fn verify_hmac_sha256_rejects_non_ascii_signature_instead_of_panicking() {
+        // Even byte length, but "é" is a 2-byte, 1-char UTF-8 sequence, so
+        // naive byte-offset slicing would land mid-codepoint.
+        assert!(!verify_hmac_sha256(b"secret", b"payload", "aéa"));
+    }
+
+    #[test]
+    fn hex_decode_round_trips_known_bytes() {
+        assert_eq!(hex_decode("00ff"), Some(vec![0x00, 0xff]));
+        assert_eq!(hex_decode(""), Some(vec![]));
+        assert_eq!(hex_decode("a"), None);
+        assert_eq!(hex_decode("zz"), None);
+        assert_eq!(hex_decode("aéa"), None);
+    }
+}
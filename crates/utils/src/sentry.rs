@@ -1,28 +1,63 @@
-// Telemetry removed - this module is now a no-op stub
+// Remote (Sentry) error reporting removed. `sentry_layer` now drives an
+// opt-in, local `tokio-console` diagnostics layer instead, so maintainers
+// can attach a live console to this process and inspect its async tasks -
+// notably the log-normalization task `RalphExecutor::normalize_logs` spawns
+// per run, and each executor's stdout/stderr stream pumps.
 
 use tracing_subscriber::Layer;
 
+/// Env var that opts into the local tokio-console diagnostics layer. Off by
+/// default: the instrumentation has a small but nonzero cost, and most runs
+/// don't need a live console attached.
+const CONSOLE_ENV_VAR: &str = "VIBE_KANBAN_CONSOLE";
+
 #[derive(Clone, Copy, Debug)]
 pub enum SentrySource {
     Backend,
     Mcp,
 }
 
-/// No-op: Sentry has been removed
+/// True when `VIBE_KANBAN_CONSOLE` is set to a truthy value.
+fn console_enabled() -> bool {
+    std::env::var(CONSOLE_ENV_VAR)
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// No-op: Sentry has been removed. The console layer itself needs no
+/// separate init call - building it (in `sentry_layer`) both constructs the
+/// layer and binds its gRPC server - so this is kept only as the stable
+/// entry point callers already invoke at startup.
 pub fn init_once(_source: SentrySource) {
     // No-op - telemetry disabled
 }
 
-/// No-op: Sentry has been removed
+/// No-op: Sentry has been removed, and the console layer has no equivalent
+/// user-scope concept.
 pub fn configure_user_scope(_user_id: &str, _username: Option<&str>, _email: Option<&str>) {
     // No-op - telemetry disabled
 }
 
-/// Returns a no-op layer that does nothing
+/// Returns the `tokio-console` layer when opted into via
+/// `VIBE_KANBAN_CONSOLE`, or a no-op layer otherwise, so callers can
+/// unconditionally fold this into their subscriber stack (e.g.
+/// `registry().with(sentry_layer())`). Constructing the layer also spawns
+/// its gRPC server, so a console can attach as soon as this process starts.
 pub fn sentry_layer<S>() -> impl Layer<S>
 where
     S: tracing::Subscriber,
     S: for<'a> tracing_subscriber::registry::LookupSpan<'a>,
 {
-    tracing_subscriber::layer::Identity::new()
+    console_enabled().then(console_subscriber::spawn)
+}
+
+/// Instrumentation span for a spawned executor task, named by executor kind
+/// and worktree, so a stuck or busy-looping task (e.g. a log normalizer) is
+/// identifiable in `tokio-console` instead of showing up as anonymous.
+pub fn executor_task_span(executor_kind: &str, worktree: &std::path::Path) -> tracing::Span {
+    tracing::info_span!(
+        "executor_task",
+        executor_kind = %executor_kind,
+        worktree = %worktree.display()
+    )
 }